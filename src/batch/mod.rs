@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use crate::errors;
+use crate::wikia::{Shadow, SkipReason, Skipped};
+
+#[derive(Debug)]
+pub struct Failure {
+    pub name: String,
+    pub error: anyhow::Error
+}
+
+// One name per line, blank lines and # comments skipped
+pub fn read_names(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.to_string())
+        .collect()
+}
+
+// Looks every name up on up to `jobs` threads, results in the order the names were given.
+// A failed lookup doesn't stop the others
+pub fn lookup_all<F>(names: &[String], jobs: usize, fetch: F) -> (Vec<Shadow>, Vec<Failure>)
+where
+    F: Fn(&str) -> anyhow::Result<Shadow> + Sync
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<anyhow::Result<Shadow>>>> = Mutex::new(names.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, names.len().max(1)) {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::SeqCst);
+                let name = match names.get(idx) {
+                    Some(name) => name,
+                    None => break
+                };
+
+                let result = fetch(name);
+                if let Ok(mut results) = results.lock() {
+                    results[idx] = Some(result);
+                }
+            });
+        }
+    });
+
+    let mut shadows = vec![];
+    let mut failures = vec![];
+    let results = results.into_inner().unwrap_or_default();
+    for (name, result) in names.iter().zip(results) {
+        match result {
+            Some(Ok(shadow)) => shadows.push(shadow),
+            Some(Err(error)) => failures.push(Failure { name: name.clone(), error }),
+            None => failures.push(Failure { name: name.clone(), error: anyhow::anyhow!("never looked up") })
+        }
+    }
+
+    (shadows, failures)
+}
+
+pub fn print_failures(failures: &[Failure], total: usize) {
+    if failures.is_empty() {
+        return;
+    }
+
+    eprintln!("{}", format!("{} of {} lookups failed:", failures.len(), total).red());
+    for failure in failures {
+        eprintln!("  {}: {}", failure.name, failure.error);
+    }
+}
+
+// For the --json dump's skipped list
+pub fn skipped(failures: &[Failure]) -> Vec<Skipped> {
+    failures.iter()
+        .map(|f| Skipped {
+            name: f.name.clone(),
+            reason: SkipReason::of(&f.error),
+            message: f.error.to_string()
+        })
+        .collect()
+}
+
+// An unreachable wiki fails every lookup after it, so it wins over whatever the first failure was
+pub fn exit_code(failures: &[Failure]) -> i32 {
+    let codes: Vec<i32> = failures.iter().map(|f| errors::exit_code(&f.error)).collect();
+
+    if codes.contains(&errors::EXIT_NETWORK) {
+        errors::EXIT_NETWORK
+    } else {
+        codes.first().copied().unwrap_or(errors::EXIT_OK)
+    }
+}
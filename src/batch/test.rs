@@ -0,0 +1,68 @@
+use super::*;
+use crate::{dump, errors, utils};
+
+fn dump() -> Vec<Shadow> {
+    let (_, shadows) = dump::parse(include_str!("../../shadows.json")).unwrap();
+    shadows
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn read_names_skips_blanks_and_comments() {
+    let text = "# block 2\nCrying Table\n\n  Cowardly Maya  \n";
+    assert_eq!(read_names(text), names(&["Crying Table", "Cowardly Maya"]));
+}
+
+#[test]
+fn lookup_all_keeps_order_and_continues() {
+    let dump = dump();
+    let game = utils::determine_game("3");
+    let wanted = names(&["Crying Table", "The Hedgehog", "Cowardly Maya", "Dancing Hand"]);
+
+    let (shadows, failures) = lookup_all(&wanted, 3, |name| utils::find_shadow(&dump, name, &game));
+
+    assert_eq!(
+        shadows.iter().map(|s| s.name.as_str()).collect::<Vec<&str>>(),
+        vec!["Crying Table", "Cowardly Maya", "Dancing Hand"]
+    );
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].name, "The Hedgehog");
+    assert_eq!(exit_code(&failures), errors::EXIT_NO_SHADOW);
+}
+
+#[test]
+fn exit_code_prefers_network() {
+    let failure = |name: &str, error: anyhow::Error| Failure { name: name.to_string(), error };
+    let failures = vec![
+        failure("The Hedgehog", errors::NoShadowError { name: "The Hedgehog".to_string(), game: "Persona 3".to_string() }.into()),
+        failure("Crying Table", errors::NetworkError { url: "http://wiki".to_string(), reason: "timed out".to_string() }.into())
+    ];
+
+    assert_eq!(exit_code(&failures), errors::EXIT_NETWORK);
+    assert_eq!(exit_code(&failures[..1]), errors::EXIT_NO_SHADOW);
+    assert_eq!(exit_code(&[]), errors::EXIT_OK);
+}
+
+#[test]
+fn failures_become_skipped() {
+    let failures = vec![Failure {
+        name: "The Hedgehog".to_string(),
+        error: errors::NoShadowError { name: "The Hedgehog".to_string(), game: "Persona 3".to_string() }.into()
+    }];
+
+    let skipped = skipped(&failures);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].name, "The Hedgehog");
+    assert_eq!(skipped[0].reason, SkipReason::NotInGame);
+    assert_eq!(skipped[0].message, failures[0].error.to_string());
+}
+
+#[test]
+fn lookup_all_nothing() {
+    let (shadows, failures) = lookup_all(&[], 4, |name| anyhow::bail!("looked up {}", name));
+    assert!(shadows.is_empty());
+    assert!(failures.is_empty());
+}
@@ -0,0 +1,381 @@
+#[cfg(test)]
+mod test;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+use std::collections::HashMap;
+use crate::source::Source;
+use crate::utils;
+use crate::wikia::{Game, Shadow, ShadowInfo, Skipped, Stats};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Focus {
+    Filter,
+    Elements
+}
+
+pub struct App {
+    pub game: Game,
+    // in the order of the arcana index
+    pub names: Vec<String>,
+    // keyed by lowercased name, filled in as shadows get selected
+    pub shadows: HashMap<String, Shadow>,
+    pub all_loaded: bool,
+    pub filter: String,
+    pub elements: Vec<&'static str>,
+    // only shadows weak to every toggled element are listed
+    pub weak_to: Vec<bool>,
+    // the element toggled before the whole game was loaded, browse() loads it and then toggles
+    pub pending_toggle: Option<usize>,
+    pub focus: Focus,
+    pub element_cursor: usize,
+    pub selected: usize,
+    pub scroll: u16,
+    pub status: Option<String>
+}
+
+impl App {
+    pub fn new(source: &dyn Source, game: Game) -> anyhow::Result<App> {
+        let elements = utils::game_elements(&game);
+
+        Ok(App {
+            names: source.names(&game)?,
+            game,
+            shadows: HashMap::new(),
+            all_loaded: false,
+            filter: String::new(),
+            weak_to: vec![false; elements.len()],
+            pending_toggle: None,
+            elements,
+            focus: Focus::Filter,
+            element_cursor: 0,
+            selected: 0,
+            scroll: 0,
+            status: None
+        })
+    }
+
+    fn toggled(&self) -> Vec<&'static str> {
+        self.elements.iter()
+            .zip(&self.weak_to)
+            .filter(|(_, on)| **on)
+            .map(|(e, _)| *e)
+            .collect()
+    }
+
+    fn weak_to_toggled(&self, shadow: &Shadow) -> bool {
+        let toggled = self.toggled();
+        shadow.info.iter().any(|i| toggled.iter().all(|e| i.affinity(e) == "Weak"))
+    }
+
+    pub fn visible(&self) -> Vec<&String> {
+        let filter = self.filter.to_lowercase();
+        let any_toggled = self.weak_to.iter().any(|on| *on);
+
+        self.names.iter()
+            .filter(|name| name.to_lowercase().contains(&filter))
+            .filter(|name| !any_toggled || self.shadows.get(&name.to_lowercase())
+                .is_some_and(|s| self.weak_to_toggled(s)))
+            .collect()
+    }
+
+    pub fn selected_shadow(&self) -> Option<&Shadow> {
+        let name = self.visible().get(self.selected)?.to_lowercase();
+        self.shadows.get(&name)
+    }
+
+    // fetches the selected shadow unless it's already in memory
+    pub fn load_selected(&mut self, source: &dyn Source) {
+        let name = match self.visible().get(self.selected) {
+            Some(name) => name.to_string(),
+            None => return
+        };
+        if self.shadows.contains_key(&name.to_lowercase()) {
+            return;
+        }
+
+        match source.shadow(&name, &self.game) {
+            Ok(shadow) => {
+                self.shadows.insert(name.to_lowercase(), shadow);
+            },
+            Err(e) => self.status = Some(e.to_string())
+        }
+    }
+
+    // the element filters need every shadow, what Source::shadows_with_progress loaded for the
+    // first toggle goes in here. Skipped shadows end up in the status line, not on stderr
+    pub fn finish_loading(&mut self, loaded: anyhow::Result<(Vec<Shadow>, Vec<Skipped>)>) {
+        let pending = self.pending_toggle.take();
+
+        match loaded {
+            Ok((shadows, skipped)) => {
+                for shadow in shadows {
+                    self.shadows.insert(shadow.name.to_lowercase(), shadow);
+                }
+                self.all_loaded = true;
+
+                if !skipped.is_empty() {
+                    let names: Vec<&str> = skipped.iter().map(|s| s.name.as_str()).collect();
+                    self.status = Some(format!("{} shadows couldn't be read and aren't listed: {}", names.len(), names.join(", ")));
+                }
+                if let Some(element) = pending {
+                    self.toggle(element);
+                }
+            },
+            Err(e) => self.status = Some(e.to_string())
+        }
+    }
+
+    fn toggle(&mut self, element: usize) {
+        self.weak_to[element] = !self.weak_to[element];
+        self.select(0);
+    }
+
+    fn select(&mut self, selected: usize) {
+        self.selected = selected;
+        self.scroll = 0;
+    }
+
+    // false once the user wants out
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+
+        let visible = self.visible().len();
+        match (self.focus, key.code) {
+            (_, KeyCode::Esc) if self.focus == Focus::Filter && !self.filter.is_empty() => {
+                self.filter.clear();
+                self.select(0);
+            },
+            (_, KeyCode::Esc) => return false,
+            (Focus::Elements, KeyCode::Char('q')) => return false,
+            (_, KeyCode::Tab) => {
+                self.focus = match self.focus {
+                    Focus::Filter => Focus::Elements,
+                    Focus::Elements => Focus::Filter
+                };
+            },
+            (_, KeyCode::Down) if self.selected + 1 < visible => self.select(self.selected + 1),
+            (_, KeyCode::Up) if self.selected > 0 => self.select(self.selected - 1),
+            (_, KeyCode::PageDown) => self.scroll = self.scroll.saturating_add(5),
+            (_, KeyCode::PageUp) => self.scroll = self.scroll.saturating_sub(5),
+            (Focus::Filter, KeyCode::Char(c)) => {
+                self.filter.push(c);
+                self.select(0);
+            },
+            (Focus::Filter, KeyCode::Backspace) => {
+                self.filter.pop();
+                self.select(0);
+            },
+            (Focus::Elements, KeyCode::Left) if self.element_cursor > 0 => self.element_cursor -= 1,
+            (Focus::Elements, KeyCode::Right) if self.element_cursor + 1 < self.elements.len() => {
+                self.element_cursor += 1;
+            },
+            (Focus::Elements, KeyCode::Char(' ')) | (Focus::Elements, KeyCode::Enter) => {
+                if self.all_loaded {
+                    self.toggle(self.element_cursor);
+                } else {
+                    self.pending_toggle = Some(self.element_cursor);
+                }
+            },
+            _ => {}
+        }
+
+        true
+    }
+}
+
+fn affinity_style(affinity: &str) -> Style {
+    match affinity {
+        "Weak" => Style::default().fg(Color::Red),
+        "Strong" => Style::default().fg(Color::Blue),
+        "Null" => Style::default().fg(Color::Green),
+        "Repel" => Style::default().fg(Color::Magenta),
+        "Drain" => Style::default().fg(Color::LightGreen),
+        _ => Style::default()
+    }
+}
+
+// the same abbreviations the wiki's stat templates use
+fn affinity_abbreviation(affinity: &str) -> &str {
+    match affinity {
+        "Weak" => "Wk",
+        "Strong" => "Str",
+        "Null" => "Nu",
+        "Repel" => "Rpl",
+        "Drain" => "Dr",
+        _ => "-"
+    }
+}
+
+fn stats_line(stats: &Stats) -> Line<'static> {
+    let fields = [
+        ("Lv", stats.level), ("HP", stats.hp), ("SP", stats.sp), ("St", stats.strength),
+        ("Ma", stats.magic), ("En", stats.endurance), ("Ag", stats.agility), ("Lu", stats.luck)
+    ];
+
+    Line::from(fields.iter()
+        .filter_map(|(label, value)| value.map(|v| format!("{} {}", label, v)))
+        .collect::<Vec<String>>()
+        .join("  "))
+}
+
+fn variant_lines(info: &ShadowInfo, elements: &[&'static str]) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(Span::styled(info.variant.clone(), Style::default().add_modifier(Modifier::BOLD)))];
+
+    lines.push(Line::from(elements.iter()
+        .map(|e| Span::raw(format!("{:7}", e)))
+        .collect::<Vec<Span>>()));
+    lines.push(Line::from(elements.iter()
+        .map(|e| {
+            let affinity = info.affinity(e);
+            Span::styled(format!("{:7}", affinity_abbreviation(&affinity)), affinity_style(&affinity))
+        })
+        .collect::<Vec<Span>>()));
+
+    if let Some(stats) = &info.stats {
+        lines.push(stats_line(stats));
+    }
+    for skill in &info.skills {
+        if skill.effect.is_empty() {
+            lines.push(Line::from(format!("  {}", skill.name)));
+        } else {
+            lines.push(Line::from(format!("  {} - {}", skill.name, skill.effect)));
+        }
+    }
+    for warning in &info.warnings {
+        lines.push(Line::from(Span::styled(format!("warning: {}", warning), Style::default().fg(Color::Yellow))));
+    }
+
+    lines.push(Line::from(""));
+    lines
+}
+
+fn detail_lines(shadow: &Shadow, elements: &[&'static str]) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    if !shadow.arcana.is_empty() {
+        lines.push(Line::from(format!("Arcana: {}", shadow.arcana.join(", "))));
+    }
+    if let Some(kind) = shadow.kind {
+        lines.push(Line::from(format!("Kind: {:?}", kind)));
+    }
+    lines.push(Line::from(""));
+
+    for info in &shadow.info {
+        lines.extend(variant_lines(info, elements));
+    }
+
+    lines
+}
+
+fn focused(block: Block, is_focused: bool) -> Block {
+    if is_focused {
+        block.border_style(Style::default().fg(Color::Yellow))
+    } else {
+        block
+    }
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    draw_loading(frame, app, None);
+}
+
+// the usual screen, with (done, total) in the status line while the whole game loads
+pub fn draw_loading(frame: &mut Frame, app: &App, progress: Option<(usize, usize)>) {
+    let [filter_area, main_area, elements_area, help_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(5),
+        Constraint::Length(3),
+        Constraint::Length(1)
+    ]).areas(frame.area());
+    let [list_area, detail_area] = Layout::horizontal([
+        Constraint::Percentage(30),
+        Constraint::Percentage(70)
+    ]).areas(main_area);
+
+    let filter = Paragraph::new(app.filter.as_str())
+        .block(focused(Block::default().borders(Borders::ALL).title("Filter"), app.focus == Focus::Filter));
+    frame.render_widget(filter, filter_area);
+
+    let visible = app.visible();
+    let items: Vec<ListItem> = visible.iter().map(|name| ListItem::new(name.as_str())).collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(format!("{} ({})", app.game.entry_text, visible.len())))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(if visible.is_empty() { None } else { Some(app.selected) });
+    frame.render_stateful_widget(list, list_area, &mut state);
+
+    let (title, lines) = match app.selected_shadow() {
+        Some(shadow) => (shadow.name.clone(), detail_lines(shadow, &app.elements)),
+        None => (String::new(), vec![])
+    };
+    let detail = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((app.scroll, 0));
+    frame.render_widget(detail, detail_area);
+
+    let toggles: Vec<Span> = app.elements.iter().enumerate().flat_map(|(idx, element)| {
+        let mut style = if app.weak_to[idx] {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        if app.focus == Focus::Elements && idx == app.element_cursor {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        vec![Span::styled(element.to_string(), style), Span::raw(" ")]
+    }).collect();
+    let toggles = Paragraph::new(Line::from(toggles))
+        .block(focused(Block::default().borders(Borders::ALL).title("Weak to"), app.focus == Focus::Elements));
+    frame.render_widget(toggles, elements_area);
+
+    let help = match (progress, &app.status) {
+        (Some((done, total)), _) => Line::from(Span::styled(
+            format!("loading every shadow for the element filters: {}/{}", done, total),
+            Style::default().fg(Color::Yellow)
+        )),
+        (None, Some(status)) => Line::from(Span::styled(status.clone(), Style::default().fg(Color::Red))),
+        (None, None) => Line::from("type to filter, up/down to pick, tab for elements, space toggles, pgup/pgdn scroll, esc quits")
+    };
+    frame.render_widget(Paragraph::new(help), help_area);
+}
+
+pub fn browse(source: &dyn Source, game: Game) -> anyhow::Result<()> {
+    let mut app = App::new(source, game)?;
+
+    let mut terminal = ratatui::init();
+    let result = (|| -> anyhow::Result<()> {
+        loop {
+            app.load_selected(source);
+            terminal.draw(|frame| draw(frame, &app))?;
+
+            if app.pending_toggle.is_some() {
+                let loaded = source.shadows_with_progress(&app.game, &mut |done, total| {
+                    let _ = terminal.draw(|frame| draw_loading(frame, &app, Some((done, total))));
+                });
+                app.finish_loading(loaded);
+                continue;
+            }
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                app.status = None;
+                if !app.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+    ratatui::restore();
+
+    result
+}
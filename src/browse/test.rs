@@ -0,0 +1,110 @@
+use super::*;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use crate::dump;
+use crate::source::Dump;
+use crate::wikia::SkipReason;
+
+fn source() -> Dump {
+    let (_, shadows) = dump::parse(include_str!("../../shadows.json")).unwrap();
+    Dump { shadows }
+}
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        app.handle_key(key(KeyCode::Char(c)));
+    }
+}
+
+#[test]
+fn filter_narrows_list() {
+    let source = source();
+    let mut app = App::new(&source, utils::determine_game("3")).unwrap();
+    let all = app.visible().len();
+
+    type_text(&mut app, "table");
+    let visible = app.visible();
+    assert!(visible.len() < all);
+    assert!(visible.iter().all(|n| n.to_lowercase().contains("table")));
+
+    app.handle_key(key(KeyCode::Esc));
+    assert_eq!(app.visible().len(), all);
+    assert!(!app.handle_key(key(KeyCode::Esc)));
+}
+
+#[test]
+fn element_toggles_load_and_filter() {
+    let source = source();
+    let mut app = App::new(&source, utils::determine_game("3")).unwrap();
+
+    app.handle_key(key(KeyCode::Tab));
+    let fire = app.elements.iter().position(|e| *e == "Fire").unwrap();
+    for _ in 0..fire {
+        app.handle_key(key(KeyCode::Right));
+    }
+    app.handle_key(key(KeyCode::Char(' ')));
+    assert_eq!(app.pending_toggle, Some(fire));
+    assert!(app.visible().iter().all(|n| !app.shadows.contains_key(&n.to_lowercase())));
+
+    let mut updates = 0;
+    let loaded = source.shadows_with_progress(&app.game, &mut |_, _| updates += 1);
+    app.finish_loading(loaded);
+
+    assert!(app.all_loaded);
+    assert!(app.pending_toggle.is_none());
+    assert!(app.weak_to[fire]);
+    let visible: Vec<String> = app.visible().into_iter().cloned().collect();
+    assert!(!visible.is_empty());
+    for name in visible {
+        let shadow = &app.shadows[&name.to_lowercase()];
+        assert!(shadow.info.iter().any(|i| i.affinity("Fire") == "Weak"));
+    }
+}
+
+#[test]
+fn skipped_shadows_go_to_status() {
+    let source = source();
+    let mut app = App::new(&source, utils::determine_game("3")).unwrap();
+    app.pending_toggle = Some(0);
+
+    app.finish_loading(Ok((vec![], vec![Skipped {
+        name: "Crying Table".to_string(),
+        reason: SkipReason::Network,
+        message: "Could not reach the wiki".to_string()
+    }])));
+
+    assert!(app.all_loaded);
+    assert!(app.weak_to[0]);
+    assert!(app.status.as_deref().is_some_and(|s| s.contains("Crying Table")));
+}
+
+#[test]
+fn draws_progress() {
+    let source = source();
+    let app = App::new(&source, utils::determine_game("3")).unwrap();
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|frame| draw_loading(frame, &app, Some((12, 200)))).unwrap();
+
+    let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(screen.contains("12/200"));
+}
+
+#[test]
+fn draws_selected_shadow() {
+    let source = source();
+    let mut app = App::new(&source, utils::determine_game("3")).unwrap();
+    type_text(&mut app, "crying table");
+    app.load_selected(&source);
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|frame| draw(frame, &app)).unwrap();
+
+    let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(screen.contains("Crying Table"));
+    assert!(screen.contains("Slash"));
+}
@@ -0,0 +1,53 @@
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+// wiki edits to shadow pages are rare, a week old copy is plenty fresh
+const MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+// $LIZ_CACHE_DIR, falling back to the usual XDG location
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("LIZ_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+
+    match env::var_os("XDG_CACHE_HOME") {
+        Some(dir) => Some(PathBuf::from(dir).join("liz")),
+        None => env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache").join("liz"))
+    }
+}
+
+fn entry_path(key: &str) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+
+    cache_dir().map(|dir| dir.join(format!("{:016x}.json", hasher.finish())))
+}
+
+pub fn read(key: &str) -> Option<String> {
+    written_at(key)?;
+    fs::read_to_string(entry_path(key)?).ok()
+}
+
+// when the entry was last fetched, as long as it's still fresh enough to be read
+pub fn written_at(key: &str) -> Option<SystemTime> {
+    let modified = fs::metadata(entry_path(key)?).and_then(|m| m.modified()).ok()?;
+
+    match SystemTime::now().duration_since(modified) {
+        Ok(age) if age > MAX_AGE => None,
+        _ => Some(modified)
+    }
+}
+
+// the cache is only an optimization, failing to write to it isn't worth stopping for
+pub fn write(key: &str, body: &str) {
+    if let Some(path) = entry_path(key) {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(path, body);
+    }
+}
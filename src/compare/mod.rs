@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use crate::errors::NoVariantError;
+use crate::utils;
+use crate::wikia::{Game, Shadow};
+
+#[derive(Debug)]
+pub struct Row {
+    pub element: String,
+    pub affinities: Vec<String>,
+    // every column is weak to this element
+    pub all_weak: bool,
+    // at least one column repels or drains this element
+    pub punishes: bool
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    pub columns: Vec<String>,
+    pub rows: Vec<Row>
+}
+
+// Lines up every variant of every given shadow per element
+pub fn compare(shadows: &[Shadow], elements: &[&str]) -> Comparison {
+    let infos: Vec<(String, _)> = shadows.iter()
+        .flat_map(|s| s.info.iter().map(move |i| (format!("{} ({})", s.name, i.variant), i)))
+        .collect();
+
+    let rows = elements.iter().map(|element| {
+        let affinities: Vec<String> = infos.iter().map(|(_, info)| info.affinity(element)).collect();
+
+        Row {
+            element: element.to_string(),
+            all_weak: !affinities.is_empty() && affinities.iter().all(|a| a == "Weak"),
+            punishes: affinities.iter().any(|a| a == "Repel" || a == "Drain"),
+            affinities
+        }
+    }).collect();
+
+    Comparison {
+        columns: infos.into_iter().map(|(column, _)| column).collect(),
+        rows
+    }
+}
+
+// Split "name:variant" arguments, the variant part being optional
+pub fn parse_target(target: &str) -> (String, Option<String>) {
+    match target.split_once(':') {
+        Some((name, variant)) => (name.trim().to_string(), Some(variant.trim().to_string())),
+        None => (target.trim().to_string(), None)
+    }
+}
+
+// Keeps only the variants whose name contains the requested one, or without one the variant
+// preferred for the game, so each shadow is a single column. A variant the shadow doesn't
+// have is an error listing the ones it does, rather than an empty column
+pub fn retain_variant(shadow: &mut Shadow, variant: &Option<String>, game: &Game) -> anyhow::Result<()> {
+    let variant = match variant {
+        Some(v) => v.to_lowercase(),
+        None => {
+            shadow.info = utils::preferred_info(shadow, game).cloned().into_iter().collect();
+            return Ok(());
+        }
+    };
+
+    let available: Vec<String> = shadow.info.iter().map(|i| i.variant.clone()).collect();
+    shadow.info.retain(|i| i.variant.to_lowercase().contains(&variant));
+
+    if shadow.info.is_empty() {
+        return Err(NoVariantError {
+            shadow_name: shadow.name.clone(),
+            game: game.entry_text.clone(),
+            variant: available
+        }.into());
+    }
+
+    Ok(())
+}
+
+fn colored_affinity(affinity: &str, width: usize) -> ColoredString {
+    let padded = format!("{:width$}", if affinity == "Neutral" { "-" } else { affinity }, width = width);
+    match affinity {
+        "Weak" => padded.red(),
+        "Strong" => padded.blue(),
+        "Null" => padded.green(),
+        "Repel" => padded.purple(),
+        "Drain" => padded.bright_green(),
+        _ => padded.normal()
+    }
+}
+
+pub fn print_comparison(comparison: &Comparison) {
+    let element_width = comparison.rows.iter().map(|r| r.element.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = comparison.columns.iter().map(|c| c.len().max(6)).collect();
+
+    print!("{:width$}  ", "", width = element_width);
+    for (column, width) in comparison.columns.iter().zip(&widths) {
+        print!("{:width$}  ", column, width = width);
+    }
+    println!();
+
+    for row in &comparison.rows {
+        print!("{:width$}  ", row.element, width = element_width);
+        for (affinity, width) in row.affinities.iter().zip(&widths) {
+            print!("{}  ", colored_affinity(affinity, *width));
+        }
+
+        if row.all_weak {
+            print!("{}", "<- hits all weak".red().bold());
+        } else if row.punishes {
+            print!("{}", "<- repelled/drained".purple().bold());
+        }
+        println!();
+    }
+}
@@ -0,0 +1,72 @@
+use super::*;
+use crate::utils;
+
+fn reference_shadows(names: &[&str]) -> Vec<Shadow> {
+    let dump: Vec<Shadow> = serde_json::from_str(include_str!("../../shadows.json")).unwrap();
+    dump.into_iter().filter(|s| names.contains(&s.name.as_str())).collect()
+}
+
+#[test]
+fn compare_aligns_every_variant() {
+    let shadows = reference_shadows(&["Cowardly Maya", "Crying Table"]);
+    let game = utils::determine_game("3");
+    let comparison = compare(&shadows, &utils::game_elements(&game));
+
+    let variants: usize = shadows.iter().map(|s| s.info.len()).sum();
+    assert_eq!(comparison.columns.len(), variants);
+    assert_eq!(comparison.rows.len(), 10);
+    assert!(comparison.rows.iter().all(|r| r.affinities.len() == variants));
+}
+
+#[test]
+fn compare_highlights() {
+    let mut shadows = reference_shadows(&["Cowardly Maya"]);
+    let game = utils::determine_game("3");
+    retain_variant(&mut shadows[0], &Some("answer".to_string()), &game).unwrap();
+    let comparison = compare(&shadows, &utils::game_elements(&game));
+
+    let fire = comparison.rows.iter().find(|r| r.element == "Fire").unwrap();
+    assert!(fire.all_weak);
+    assert!(!fire.punishes);
+
+    let ice = comparison.rows.iter().find(|r| r.element == "Ice").unwrap();
+    assert!(!ice.all_weak);
+    assert!(ice.punishes);
+}
+
+#[test]
+fn parse_target_variant() {
+    assert_eq!(parse_target("crying table"), ("crying table".to_string(), None));
+    assert_eq!(
+        parse_target("crying table: the answer"),
+        ("crying table".to_string(), Some("the answer".to_string()))
+    );
+}
+
+#[test]
+fn retain_preferred_variant_per_game() {
+    let game = utils::determine_game("3");
+    let mut shadows = reference_shadows(&["Cowardly Maya", "Crying Table"]);
+    for shadow in &mut shadows {
+        retain_variant(shadow, &None, &game).unwrap();
+    }
+    let comparison = compare(&shadows, &utils::game_elements(&game));
+    assert_eq!(comparison.columns, vec!["Cowardly Maya (The Journey)", "Crying Table (The Journey)"]);
+
+    let answer = utils::determine_game("3a");
+    let mut shadow = reference_shadows(&["Crying Table"]).remove(0);
+    retain_variant(&mut shadow, &None, &answer).unwrap();
+    assert_eq!(shadow.info.len(), 1);
+    assert_eq!(shadow.info[0].variant, "The Answer");
+}
+
+#[test]
+fn retain_unknown_variant() {
+    let mut shadows = reference_shadows(&["Cowardly Maya"]);
+    let game = utils::determine_game("3");
+
+    let err = retain_variant(&mut shadows[0], &Some("anwser".to_string()), &game).unwrap_err();
+    let err = err.downcast::<NoVariantError>().unwrap();
+    assert_eq!(err.shadow_name, "Cowardly Maya");
+    assert!(err.variant.iter().any(|v| v == "The Answer"));
+}
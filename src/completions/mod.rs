@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod test;
+
+use crate::errors::UsageError;
+use crate::utils;
+
+// every subcommand, kept in step with main's Command enum by the tests
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("search", "find shadows by affinity"),
+    ("compare", "affinities of several shadows side by side"),
+    ("plan", "rank elements against an encounter"),
+    ("coverage", "check a party's elements against an area"),
+    ("where", "where a shadow appears"),
+    ("area", "shadows appearing in an area"),
+    ("diff", "compare two dumps"),
+    ("verify", "check a dump against the wiki"),
+    ("export", "write a dump to SQLite"),
+    ("serve", "serve lookups as JSON over HTTP"),
+    ("shell", "interactive lookups"),
+    ("browse", "full-screen shadow browser"),
+    ("completions", "print a shell completion script"),
+    ("list", "shadows by arcana from the index page"),
+    ("lookup", "look up several shadows from a file or stdin")
+];
+
+const FLAGS: &[&str] = &[
+    "-s", "--shadow", "-p", "--persona", "-a", "--all", "-v", "--verbose", "-k", "--kind",
+    "--debug-parse", "--wikitext", "--wiki-url", "--bare", "--help"
+];
+
+// subcommands whose positional arguments are shadow names
+const NAME_COMMANDS: &str = "compare plan where";
+
+const BASH: &str = r#"_liz_names() {
+    local IFS=$'\n' name typed="${cur#[\"\']}"
+    typed="${typed//\\ / }"
+    COMPREPLY=()
+    for name in $(liz completions names -p "$game" 2>/dev/null); do
+        [[ "${name,,}" == "${typed,,}"* ]] && COMPREPLY+=("$(printf '%q' "$name")")
+    done
+}
+
+_liz() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local game=3 command="" i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            -p|--persona) game="${COMP_WORDS[i+1]}" ;;
+            @COMMAND_PATTERN@) [[ -z "$command" ]] && command="${COMP_WORDS[i]}" ;;
+        esac
+    done
+
+    case "$prev" in
+        -p|--persona) COMPREPLY=($(compgen -W "@ALIASES@" -- "$cur")); return ;;
+        -s|--shadow) _liz_names; return ;;
+        -f|--file|-o|--output) COMPREPLY=($(compgen -f -- "$cur")); return ;;
+    esac
+
+    case "$command" in
+        "") COMPREPLY=($(compgen -W "@COMMANDS@ @FLAGS@" -- "$cur")) ;;
+        @NAME_PATTERN@) _liz_names ;;
+        completions) COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur")) ;;
+    esac
+}
+
+complete -F _liz liz
+"#;
+
+const ZSH: &str = r#"#compdef liz
+
+_liz_names() {
+    local -a names
+    names=("${(@f)$(liz completions names -p "$game" 2>/dev/null)}")
+    compadd -M 'm:{a-zA-Z}={A-Za-z}' -- "${names[@]}"
+}
+
+_liz() {
+    local game=3 command="" i
+    for ((i = 2; i < CURRENT; i++)); do
+        case "${words[i]}" in
+            -p|--persona) game="${words[i+1]}" ;;
+            @COMMAND_PATTERN@) [[ -z "$command" ]] && command="${words[i]}" ;;
+        esac
+    done
+
+    case "${words[CURRENT-1]}" in
+        -p|--persona) compadd -- @ALIASES@; return ;;
+        -s|--shadow) _liz_names; return ;;
+        -f|--file|-o|--output) _files; return ;;
+    esac
+
+    case "$command" in
+        "") compadd -- @COMMANDS@ @FLAGS@ ;;
+        @NAME_PATTERN@) _liz_names ;;
+        completions) compadd -- bash zsh fish ;;
+    esac
+}
+
+compdef _liz liz
+"#;
+
+const FISH: &str = r#"function __liz_game
+    set -l tokens (commandline -opc)
+    set -l game 3
+    for i in (seq (count $tokens))
+        if contains -- $tokens[$i] -p --persona; and test $i -lt (count $tokens)
+            set game $tokens[(math $i + 1)]
+        end
+    end
+    echo $game
+end
+
+function __liz_names
+    liz completions names -p (__liz_game) 2>/dev/null
+end
+
+complete -c liz -f
+@FISH_COMMANDS@
+complete -c liz -s p -l persona -x -a "@ALIASES@"
+complete -c liz -s s -l shadow -x -a "(__liz_names)"
+complete -c liz -s k -l kind -x -a "boss miniboss normal"
+complete -c liz -s f -l file -r -F
+complete -c liz -s o -l output -r -F
+complete -c liz -n "__fish_seen_subcommand_from @NAME_COMMANDS@" -a "(__liz_names)"
+complete -c liz -n "__fish_seen_subcommand_from completions" -a "bash zsh fish"
+"#;
+
+pub fn script(shell: &str) -> anyhow::Result<String> {
+    let commands: Vec<&str> = COMMANDS.iter().map(|(c, _)| *c).collect();
+    let template = match shell {
+        "bash" => BASH,
+        "zsh" => ZSH,
+        "fish" => FISH,
+        other => return Err(UsageError {
+            reason: format!("Can't complete for {}, pick one of: bash, zsh, fish", other)
+        }.into())
+    };
+
+    let fish_commands: Vec<String> = COMMANDS.iter()
+        .map(|(c, description)| format!("complete -c liz -n __fish_use_subcommand -a {} -d \"{}\"", c, description))
+        .collect();
+
+    Ok(template
+        .replace("@COMMAND_PATTERN@", &commands.join("|"))
+        .replace("@COMMANDS@", &commands.join(" "))
+        .replace("@FISH_COMMANDS@", &fish_commands.join("\n"))
+        .replace("@NAME_PATTERN@", &NAME_COMMANDS.replace(' ', "|"))
+        .replace("@NAME_COMMANDS@", NAME_COMMANDS)
+        .replace("@ALIASES@", &utils::GAME_ALIASES.join(" "))
+        .replace("@FLAGS@", &FLAGS.join(" ")))
+}
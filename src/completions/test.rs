@@ -0,0 +1,47 @@
+use argh::FromArgs;
+use super::*;
+use crate::Opts;
+
+#[test]
+fn commands_are_subcommands() {
+    for (command, _) in COMMANDS {
+        let early_exit = Opts::from_args(&["liz"], &[command, "--help"]).err().unwrap();
+        assert!(early_exit.status.is_ok(), "{} isn't a subcommand", command);
+    }
+
+    let early_exit = Opts::from_args(&["liz"], &["dance", "--help"]).err().unwrap();
+    assert!(early_exit.status.is_err());
+}
+
+#[test]
+fn subcommands_are_listed() {
+    let help = Opts::from_args(&["liz"], &["--help"]).err().unwrap().output;
+    let subcommands: Vec<&str> = help.lines()
+        .skip_while(|line| !line.starts_with("Commands:"))
+        .skip(1)
+        .filter(|line| line.starts_with("  ") && !line.starts_with("   "))
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+
+    assert_eq!(subcommands.len(), COMMANDS.len());
+    for subcommand in subcommands {
+        assert!(COMMANDS.iter().any(|(c, _)| *c == subcommand), "{} isn't in COMMANDS", subcommand);
+    }
+}
+
+#[test]
+fn scripts_fill_in_everything() {
+    for shell in &["bash", "zsh", "fish"] {
+        let script = script(shell).unwrap();
+        let placeholder_left = script.split('@').skip(1).any(|part| part.starts_with(char::is_uppercase));
+        assert!(!placeholder_left, "{} script has a placeholder left", shell);
+        assert!(script.contains("browse"));
+        assert!(script.contains("3 3j 3a 4 4g"));
+        assert!(script.contains("liz completions names -p"));
+    }
+}
+
+#[test]
+fn unknown_shell() {
+    assert!(script("powershell").is_err());
+}
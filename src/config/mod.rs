@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod test;
+
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use crate::errors::UsageError;
+
+pub const DEFAULT_WIKI_URL: &str = "https://megamitensei.fandom.com";
+
+// ~/.config/liz/config.json, e.g. {"wiki_url": "http://localhost:8080"}
+#[derive(Deserialize, Debug, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub wiki_url: Option<String>
+}
+
+static WIKI_URL_FLAG: OnceLock<Option<String>> = OnceLock::new();
+static WIKI_URL: OnceLock<String> = OnceLock::new();
+static CHECKED: OnceLock<()> = OnceLock::new();
+
+// $LIZ_CONFIG, falling back to the usual XDG location
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("LIZ_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+
+    match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => Some(PathBuf::from(dir).join("liz").join("config.json")),
+        None => env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("liz").join("config.json"))
+    }
+}
+
+// no config file is fine, a broken one isn't
+pub fn read_config() -> anyhow::Result<Config> {
+    let path = match config_path() {
+        Some(p) if p.exists() => p,
+        _ => return Ok(Config::default())
+    };
+
+    match serde_json::from_str(&fs::read_to_string(&path)?) {
+        Ok(config) => Ok(config),
+        Err(e) => anyhow::bail!("Invalid config file: {} ({})", path.display(), e)
+    }
+}
+
+// the flag, then $LIZ_WIKI_URL, then the config file, then fandom
+pub fn resolve_wiki_url(flag: Option<&str>, env_url: Option<String>, config: &Config) -> anyhow::Result<String> {
+    let url = flag.map(|f| f.to_string())
+        .or(env_url)
+        .or_else(|| config.wiki_url.clone())
+        .unwrap_or_else(|| DEFAULT_WIKI_URL.to_string());
+
+    let url = url.trim().trim_end_matches('/').to_string();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(UsageError {
+            reason: format!("Invalid wiki url: {}, expected it to start with http:// or https://", url)
+        }.into());
+    }
+
+    Ok(url)
+}
+
+// Called once at startup with --wiki-url. Nothing is read until the wiki is needed, so commands
+// that never fetch anything don't fail over a broken config file
+pub fn init(flag: Option<&str>) {
+    let _ = WIKI_URL_FLAG.set(flag.map(|f| f.to_string()));
+}
+
+fn resolve() -> anyhow::Result<String> {
+    let flag = WIKI_URL_FLAG.get().and_then(|f| f.as_deref());
+    resolve_wiki_url(flag, env::var("LIZ_WIKI_URL").ok(), &read_config()?)
+}
+
+// The error a bad flag, variable or config file makes, checked before every request
+pub fn check() -> anyhow::Result<()> {
+    if CHECKED.get().is_none() {
+        resolve()?;
+        let _ = CHECKED.set(());
+    }
+
+    Ok(())
+}
+
+// Base url every wiki request goes to, without a trailing slash. Falls back to fandom when
+// it can't be worked out, check() is what reports why
+pub fn wiki_url() -> &'static str {
+    WIKI_URL.get_or_init(|| resolve().unwrap_or_else(|_| DEFAULT_WIKI_URL.to_string()))
+}
@@ -0,0 +1,29 @@
+use super::*;
+
+#[test]
+fn resolve_precedence() {
+    let config = Config {
+        wiki_url: Some("http://mirror.example".to_string())
+    };
+    let env_url = Some("http://localhost:8080/".to_string());
+
+    assert_eq!(resolve_wiki_url(Some("https://flag.example"), env_url.clone(), &config).unwrap(), "https://flag.example");
+    assert_eq!(resolve_wiki_url(None, env_url, &config).unwrap(), "http://localhost:8080");
+    assert_eq!(resolve_wiki_url(None, None, &config).unwrap(), "http://mirror.example");
+    assert_eq!(resolve_wiki_url(None, None, &Config::default()).unwrap(), DEFAULT_WIKI_URL);
+}
+
+#[test]
+fn resolve_rejects_non_http() {
+    assert!(resolve_wiki_url(Some("megamitensei.fandom.com"), None, &Config::default()).is_err());
+    assert!(resolve_wiki_url(Some("file:///tmp/wiki"), None, &Config::default()).is_err());
+}
+
+#[test]
+fn config_file() {
+    let config: Config = serde_json::from_str(r#"{"wiki_url": "http://archive.example/megaten"}"#).unwrap();
+    assert_eq!(config.wiki_url.unwrap(), "http://archive.example/megaten");
+
+    let config: Config = serde_json::from_str("{}").unwrap();
+    assert!(config.wiki_url.is_none());
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use crate::utils;
+use crate::wikia::{Game, Shadow};
+
+#[derive(Debug)]
+pub struct Report {
+    pub shadows: usize,
+    // none of the party's elements hit a weakness
+    pub unexploitable: Vec<String>,
+    // shadow name, and the party elements it repels or drains
+    pub punishing: Vec<(String, Vec<String>)>
+}
+
+// "Fire, Elec,pierce ice" -> ["Fire", "Elec", "Pierce", "Ice"]
+pub fn parse_party(party: &str) -> Vec<String> {
+    party.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|e| !e.is_empty())
+        .map(utils::normalize_element)
+        .collect()
+}
+
+pub fn analyze(shadows: &[Shadow], party: &[String], game: &Game) -> Report {
+    let mut report = Report {
+        shadows: shadows.len(),
+        unexploitable: vec![],
+        punishing: vec![]
+    };
+
+    for shadow in shadows {
+        let info = match utils::preferred_info(shadow, game) {
+            Some(i) => i,
+            None => continue
+        };
+
+        if !party.iter().any(|e| info.affinity(e) == "Weak") {
+            report.unexploitable.push(shadow.name.clone());
+        }
+
+        let punished: Vec<String> = party.iter()
+            .filter(|e| {
+                let affinity = info.affinity(e);
+                affinity == "Repel" || affinity == "Drain"
+            })
+            .map(|e| format!("{} ({})", e, info.affinity(e)))
+            .collect();
+        if !punished.is_empty() {
+            report.punishing.push((shadow.name.clone(), punished));
+        }
+    }
+
+    report
+}
+
+pub fn print_report(report: &Report, area: &str) {
+    println!("{} shadow(s) found in {}", report.shadows, area);
+    println!();
+
+    println!("{}", "No weakness the party can exploit".bold());
+    for name in &report.unexploitable {
+        println!("  {}", name.yellow());
+    }
+    if report.unexploitable.is_empty() {
+        println!("  none, everything here can be knocked down");
+    }
+
+    println!();
+    println!("{}", "Punishes the party".bold());
+    for (name, elements) in &report.punishing {
+        println!("  {}: {}", name.purple(), elements.join(", "));
+    }
+    if report.punishing.is_empty() {
+        println!("  none");
+    }
+}
@@ -0,0 +1,32 @@
+use super::*;
+
+fn reference_shadows(names: &[&str]) -> Vec<Shadow> {
+    let dump: Vec<Shadow> = serde_json::from_str(include_str!("../../shadows.json")).unwrap();
+    dump.into_iter().filter(|s| names.contains(&s.name.as_str())).collect()
+}
+
+#[test]
+fn parse_party_normalizes() {
+    assert_eq!(
+        parse_party("Fire, electricity,pierce ice"),
+        vec!["Fire".to_string(), "Elec".to_string(), "Pierce".to_string(), "Ice".to_string()]
+    );
+}
+
+#[test]
+fn analyze_reports_gaps_and_punishers() {
+    let game = utils::determine_game("3");
+    let shadows = reference_shadows(&["Wondrous Magus", "Sleeping Table", "Cowardly Maya"]);
+    let party = parse_party("ice, elec");
+
+    let report = analyze(&shadows, &party, &game);
+    assert_eq!(report.shadows, 3);
+    // Journey variants: the magus is only weak to fire/dark, the table has no weakness
+    assert!(report.unexploitable.contains(&"Wondrous Magus".to_string()));
+    assert!(report.unexploitable.contains(&"Sleeping Table".to_string()));
+    assert!(!report.unexploitable.contains(&"Cowardly Maya".to_string()));
+
+    let (name, elements) = report.punishing.first().unwrap();
+    assert_eq!(name, "Wondrous Magus");
+    assert_eq!(elements, &vec!["Ice (Repel)".to_string()]);
+}
@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use serde::Serialize;
+use crate::wikia::{Shadow, ShadowInfo};
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AffinityChange {
+    pub element: String,
+    pub old: String,
+    pub new: String
+}
+
+// a variant is "Persona 3: The Journey", dumps made before per-game filtering mix games
+#[derive(Serialize, Debug, PartialEq)]
+pub struct VariantChange {
+    pub variant: String,
+    pub changes: Vec<AffinityChange>
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ShadowChange {
+    pub name: String,
+    pub added_variants: Vec<String>,
+    pub removed_variants: Vec<String>,
+    pub changed_variants: Vec<VariantChange>
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct Diff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<ShadowChange>
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn variant_name(info: &ShadowInfo) -> String {
+    format!("{}: {}", info.game, info.variant)
+}
+
+// Names aren't unique, older dumps list some shadows (and variants) twice. The nth one
+// with a name is matched up with the nth one on the other side
+fn keyed<T>(items: &[T], name: impl Fn(&T) -> String) -> Vec<((String, usize), &T)> {
+    let mut keyed: Vec<((String, usize), &T)> = vec![];
+    for item in items {
+        let name = name(item).to_lowercase();
+        let nth = keyed.iter().filter(|((n, _), _)| *n == name).count();
+        keyed.push(((name, nth), item));
+    }
+
+    keyed
+}
+
+fn find<'a, T>(keyed: &[((String, usize), &'a T)], key: &(String, usize)) -> Option<&'a T> {
+    keyed.iter().find(|(k, _)| k == key).map(|(_, item)| *item)
+}
+
+// every element either side has an entry for, in the order they first show up
+fn elements(old: &ShadowInfo, new: &ShadowInfo) -> Vec<String> {
+    let mut elements: Vec<String> = vec![];
+    for info in [old, new] {
+        let mut affinities: Vec<&String> = info.resistances.keys().collect();
+        // resistances is a HashMap, sort for a stable order
+        affinities.sort();
+        for affinity in affinities {
+            for element in &info.resistances[affinity] {
+                if !elements.contains(element) {
+                    elements.push(element.clone());
+                }
+            }
+        }
+    }
+
+    elements
+}
+
+// Some tables list an element under more than one affinity, ShadowInfo::affinity would
+// pick whichever the HashMap happens to come up with first
+fn affinities(info: &ShadowInfo, element: &str) -> String {
+    let mut affinities: Vec<&str> = info.resistances.iter()
+        .filter(|(_, elements)| elements.iter().any(|e| e.eq_ignore_ascii_case(element)))
+        .map(|(affinity, _)| affinity.as_str())
+        .collect();
+    affinities.sort_unstable();
+
+    if affinities.is_empty() {
+        "Neutral".to_string()
+    } else {
+        affinities.join("/")
+    }
+}
+
+fn diff_variant(old: &ShadowInfo, new: &ShadowInfo) -> Vec<AffinityChange> {
+    elements(old, new).into_iter()
+        .filter_map(|element| {
+            let (old, new) = (affinities(old, &element), affinities(new, &element));
+            if old == new {
+                None
+            } else {
+                Some(AffinityChange { element, old, new })
+            }
+        })
+        .collect()
+}
+
+pub fn diff_shadow(old: &Shadow, new: &Shadow) -> Option<ShadowChange> {
+    let mut change = ShadowChange {
+        name: new.name.clone(),
+        added_variants: vec![],
+        removed_variants: vec![],
+        changed_variants: vec![]
+    };
+
+    let old_info = keyed(&old.info, variant_name);
+    let new_info = keyed(&new.info, variant_name);
+
+    for (key, info) in &old_info {
+        match find(&new_info, key) {
+            Some(new_info) => {
+                let changes = diff_variant(info, new_info);
+                if !changes.is_empty() {
+                    change.changed_variants.push(VariantChange { variant: variant_name(info), changes });
+                }
+            },
+            None => change.removed_variants.push(variant_name(info))
+        }
+    }
+    for (key, info) in &new_info {
+        if find(&old_info, key).is_none() {
+            change.added_variants.push(variant_name(info));
+        }
+    }
+
+    if change.added_variants.is_empty() && change.removed_variants.is_empty() && change.changed_variants.is_empty() {
+        None
+    } else {
+        Some(change)
+    }
+}
+
+// Shadows are matched by name, variants by game and variant name, ignoring case
+pub fn diff(old: &[Shadow], new: &[Shadow]) -> Diff {
+    let mut result = Diff::default();
+    let old_shadows = keyed(old, |s| s.name.clone());
+    let new_shadows = keyed(new, |s| s.name.clone());
+
+    for (key, shadow) in &old_shadows {
+        match find(&new_shadows, key) {
+            Some(new_shadow) => {
+                if let Some(change) = diff_shadow(shadow, new_shadow) {
+                    result.changed.push(change);
+                }
+            },
+            None => result.removed.push(shadow.name.clone())
+        }
+    }
+    for (key, shadow) in &new_shadows {
+        if find(&old_shadows, key).is_none() {
+            result.added.push(shadow.name.clone());
+        }
+    }
+
+    result
+}
+
+pub fn print_diff(diff: &Diff) {
+    for name in &diff.added {
+        println!("{} {}", "+".green(), name.green());
+    }
+    for name in &diff.removed {
+        println!("{} {}", "-".red(), name.red());
+    }
+
+    for change in &diff.changed {
+        println!("{} {}", "~".yellow(), change.name.bold());
+        for variant in &change.added_variants {
+            println!("    {} {}", "+".green(), variant);
+        }
+        for variant in &change.removed_variants {
+            println!("    {} {}", "-".red(), variant);
+        }
+        for variant in &change.changed_variants {
+            println!("    {}", variant.variant);
+            for c in &variant.changes {
+                println!("      {}: {} -> {}", c.element, c.old.red(), c.new.green());
+            }
+        }
+    }
+
+    if diff.is_empty() {
+        println!("No differences");
+    } else {
+        println!();
+        println!(
+            "{} added, {} removed, {} changed",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use super::*;
+
+fn reference_dump() -> Vec<Shadow> {
+    serde_json::from_str(include_str!("../../shadows.json")).unwrap()
+}
+
+#[test]
+fn diff_identical() {
+    let shadows = reference_dump();
+    assert!(diff(&shadows, &shadows).is_empty());
+}
+
+#[test]
+fn diff_added_and_removed() {
+    let old = reference_dump();
+    let mut new = old.clone();
+    let removed = new.remove(0);
+    let mut added = new[0].clone();
+    added.name = "Imaginary Maya".to_string();
+    new.push(added);
+
+    let result = diff(&old, &new);
+    assert_eq!(result.added, vec!["Imaginary Maya".to_string()]);
+    assert_eq!(result.removed, vec![removed.name]);
+    assert!(result.changed.is_empty());
+}
+
+#[test]
+fn diff_affinity_and_variants() {
+    let old = reference_dump();
+    let mut new = old.clone();
+    let table = new.iter_mut().find(|s| s.name == "Crying Table").unwrap();
+
+    // Ice goes from Weak to Null in the first variant
+    let first = &mut table.info[0];
+    first.resistances.get_mut("Weak").unwrap().retain(|e| e != "Ice");
+    first.resistances.entry("Null".to_string()).or_default().push("Ice".to_string());
+    let renamed = table.info.last_mut().unwrap();
+    let old_variant = format!("{}: {}", renamed.game, renamed.variant);
+    renamed.variant = "The Question".to_string();
+
+    let result = diff(&old, &new);
+    assert_eq!(result.changed.len(), 1);
+    let change = &result.changed[0];
+    assert_eq!(change.name, "Crying Table");
+    assert_eq!(change.removed_variants, vec![old_variant]);
+    assert_eq!(change.added_variants.len(), 1);
+    assert_eq!(change.changed_variants[0].changes, vec![AffinityChange {
+        element: "Ice".to_string(),
+        old: "Weak".to_string(),
+        new: "Null".to_string()
+    }]);
+
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["changed"][0]["changed_variants"][0]["changes"][0]["new"], "Null");
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod test;
+
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use crate::config;
+use crate::wikia::{Game, Shadow, Skipped};
+
+// Where and when a dump was made
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Metadata {
+    pub game: String,
+    // RFC 3339, UTC
+    pub generated_at: String,
+    pub liz_version: String,
+    pub wiki_url: String
+}
+
+// What -a prints. Each shadow's page id and revision are in its source
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Dump {
+    #[serde(flatten)]
+    pub metadata: Metadata,
+    pub shadows: Vec<Shadow>,
+    // index entries that didn't make it in, and why
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<Skipped>
+}
+
+impl Dump {
+    pub fn new(game: &Game, shadows: Vec<Shadow>) -> Dump {
+        Dump {
+            metadata: Metadata {
+                game: game.entry_text.clone(),
+                generated_at: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+                liz_version: env!("CARGO_PKG_VERSION").to_string(),
+                wiki_url: config::wiki_url().to_string()
+            },
+            shadows,
+            skipped: vec![]
+        }
+    }
+}
+
+// Reads both dumps with metadata and the bare arrays older versions (and --bare) print,
+// the latter having no metadata
+pub fn parse(body: &str) -> anyhow::Result<(Option<Metadata>, Vec<Shadow>)> {
+    if body.trim_start().starts_with('[') {
+        return Ok((None, serde_json::from_str(body)?));
+    }
+
+    let dump: Dump = serde_json::from_str(body)?;
+    Ok((Some(dump.metadata), dump.shadows))
+}
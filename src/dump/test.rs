@@ -0,0 +1,51 @@
+use super::*;
+use crate::utils;
+use crate::wikia::SkipReason;
+
+#[test]
+fn parse_bare() {
+    let (metadata, shadows) = parse(include_str!("../../shadows.json")).unwrap();
+
+    assert!(metadata.is_none());
+    assert!(shadows.iter().any(|s| s.name == "Crying Table"));
+}
+
+#[test]
+fn envelope_round_trip() {
+    let (_, shadows) = parse(include_str!("../../shadows.json")).unwrap();
+    let count = shadows.len();
+    let dump = Dump::new(&utils::determine_game("3"), shadows);
+
+    let body = serde_json::to_string(&dump).unwrap();
+    assert!(body.starts_with("{\"game\":\"Persona 3\""));
+
+    let (metadata, shadows) = parse(&body).unwrap();
+    assert_eq!(metadata.unwrap(), dump.metadata);
+    assert_eq!(shadows.len(), count);
+    // e.g. 2020-08-25T19:47:12Z
+    assert_eq!(dump.metadata.generated_at.len(), 20);
+    assert_eq!(dump.metadata.liz_version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn parse_garbage() {
+    assert!(parse("{\"shadows\": 3}").is_err());
+    assert!(parse("").is_err());
+}
+
+#[test]
+fn skipped_in_envelope() {
+    let mut dump = Dump::new(&utils::determine_game("3"), vec![]);
+    assert!(!serde_json::to_string(&dump).unwrap().contains("skipped"));
+
+    dump.skipped.push(Skipped {
+        name: "Nyx".to_string(),
+        reason: SkipReason::NoSection,
+        message: "no stats".to_string()
+    });
+    let body = serde_json::to_string(&dump).unwrap();
+    assert!(body.contains("\"skipped\":[{\"name\":\"Nyx\",\"reason\":\"no_section\""));
+
+    let parsed: Dump = serde_json::from_str(&body).unwrap();
+    assert_eq!(parsed.skipped, dump.skipped);
+}
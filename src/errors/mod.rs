@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod test;
+
 use core::fmt;
 
 #[derive(Debug, Clone)]
@@ -27,4 +30,114 @@ impl fmt::Display for NoVariantError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Shadow {} not found in: {} of variant: {:#?}", self.shadow_name, self.game, self.variant)
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryError {
+    pub query: String,
+    pub reason: String,
+}
+
+impl std::error::Error for QueryError {}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid query: {} ({})", self.query, self.reason)
+    }
+}
+
+// A resistance table that's missing or doesn't line up with its element headers
+#[derive(Debug, Clone)]
+pub struct TableParseError {
+    pub shadow_name: String,
+    pub game: String,
+    pub variant: String,
+    pub headers: usize,
+    pub cells: usize,
+    pub reason: String,
+}
+
+impl std::error::Error for TableParseError {}
+
+impl fmt::Display for TableParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Resistance table for {} in: {} of variant: {} could not be read: {} ({} headers, {} cells)",
+            self.shadow_name, self.game, self.variant, self.reason, self.headers, self.cells
+        )
+    }
+}
+
+// Arguments that parsed but don't make sense together
+#[derive(Debug, Clone)]
+pub struct UsageError {
+    pub reason: String,
+}
+
+impl std::error::Error for UsageError {}
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkError {
+    pub url: String,
+    pub reason: String,
+}
+
+impl std::error::Error for NetworkError {}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Could not reach the wiki at: {} ({})", self.url, self.reason)
+    }
+}
+
+// The wiki answered with something other than what was asked for
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub url: String,
+    pub reason: String,
+}
+
+impl std::error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Could not read the response from: {} ({})", self.url, self.reason)
+    }
+}
+
+// Exit codes, the README has the same table
+pub const EXIT_OK: i32 = 0;
+// diff and verify found differences, or some of a batch lookup failed
+pub const EXIT_DIFFERENCES: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NO_SHADOW: i32 = 3;
+pub const EXIT_NO_VARIANT: i32 = 4;
+pub const EXIT_TABLE_PARSE: i32 = 5;
+pub const EXIT_NETWORK: i32 = 6;
+pub const EXIT_PARSE: i32 = 7;
+pub const EXIT_OTHER: i32 = 8;
+
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    if error.is::<UsageError>() || error.is::<QueryError>() {
+        EXIT_USAGE
+    } else if error.is::<NoShadowError>() {
+        EXIT_NO_SHADOW
+    } else if error.is::<NoVariantError>() {
+        EXIT_NO_VARIANT
+    } else if error.is::<TableParseError>() {
+        EXIT_TABLE_PARSE
+    } else if error.is::<NetworkError>() {
+        EXIT_NETWORK
+    } else if error.is::<ParseError>() || error.is::<serde_json::Error>() {
+        EXIT_PARSE
+    } else {
+        EXIT_OTHER
+    }
+}
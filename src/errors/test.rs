@@ -0,0 +1,33 @@
+use super::*;
+use anyhow::Context;
+
+#[test]
+fn exit_codes() {
+    let no_shadow: anyhow::Error = NoShadowError { name: "Nyx".to_string(), game: "Persona 4".to_string() }.into();
+    let no_variant: anyhow::Error = NoVariantError {
+        shadow_name: "Nyx".to_string(),
+        game: "Persona 4".to_string(),
+        variant: vec!["Golden".to_string()]
+    }.into();
+    let network: anyhow::Error = NetworkError { url: "https://example.com".to_string(), reason: "dns".to_string() }.into();
+    let parse: anyhow::Error = ParseError { url: "https://example.com".to_string(), reason: "eof".to_string() }.into();
+    let usage: anyhow::Error = UsageError { reason: "nothing to do".to_string() }.into();
+
+    assert_eq!(exit_code(&no_shadow), EXIT_NO_SHADOW);
+    assert_eq!(exit_code(&no_variant), EXIT_NO_VARIANT);
+    assert_eq!(exit_code(&network), EXIT_NETWORK);
+    assert_eq!(exit_code(&parse), EXIT_PARSE);
+    assert_eq!(exit_code(&usage), EXIT_USAGE);
+    assert_eq!(exit_code(&anyhow::anyhow!("something else")), EXIT_OTHER);
+}
+
+#[test]
+fn exit_codes_through_context() {
+    let error = Err::<(), _>(NetworkError { url: "https://example.com".to_string(), reason: "dns".to_string() })
+        .context("looking up Nyx")
+        .unwrap_err();
+    assert_eq!(exit_code(&error), EXIT_NETWORK);
+
+    let json: anyhow::Error = serde_json::from_str::<Vec<u32>>("[").unwrap_err().into();
+    assert_eq!(exit_code(&json), EXIT_PARSE);
+}
@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod test;
+
+use rusqlite::{params, Connection};
+use crate::dump::Metadata;
+use crate::wikia::{Kind, Shadow};
+
+// One row per shadow, variant (a tab on the wiki), and per element affinity, so questions like
+// "which Answer variants drain fire" are a join away
+const SCHEMA: &str = "
+CREATE TABLE metadata (
+    game TEXT NOT NULL,
+    generated_at TEXT NOT NULL,
+    liz_version TEXT NOT NULL,
+    wiki_url TEXT NOT NULL
+);
+
+CREATE TABLE shadows (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    kind TEXT,
+    japanese_name TEXT,
+    romaji TEXT,
+    page_id INTEGER,
+    revision_id INTEGER
+);
+
+CREATE TABLE shadow_arcana (
+    shadow_id INTEGER NOT NULL REFERENCES shadows(id),
+    arcana TEXT NOT NULL
+);
+
+CREATE TABLE variants (
+    id INTEGER PRIMARY KEY,
+    shadow_id INTEGER NOT NULL REFERENCES shadows(id),
+    game TEXT NOT NULL,
+    name TEXT NOT NULL,
+    level INTEGER,
+    hp INTEGER,
+    sp INTEGER,
+    strength INTEGER,
+    magic INTEGER,
+    endurance INTEGER,
+    agility INTEGER,
+    luck INTEGER
+);
+
+CREATE TABLE affinities (
+    variant_id INTEGER NOT NULL REFERENCES variants(id),
+    element TEXT NOT NULL,
+    affinity TEXT NOT NULL
+);
+
+CREATE TABLE skills (
+    variant_id INTEGER NOT NULL REFERENCES variants(id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    effect TEXT
+);
+
+CREATE TABLE locations (
+    shadow_id INTEGER NOT NULL REFERENCES shadows(id),
+    game TEXT NOT NULL,
+    area TEXT NOT NULL,
+    region TEXT NOT NULL,
+    first_floor INTEGER,
+    last_floor INTEGER
+);
+
+CREATE INDEX shadows_name ON shadows(name);
+CREATE INDEX shadow_arcana_arcana ON shadow_arcana(arcana);
+CREATE INDEX variants_shadow ON variants(shadow_id);
+CREATE INDEX variants_game_name ON variants(game, name);
+CREATE INDEX affinities_variant ON affinities(variant_id);
+CREATE INDEX affinities_element ON affinities(element, affinity);
+CREATE INDEX skills_variant ON skills(variant_id);
+CREATE INDEX skills_name ON skills(name);
+CREATE INDEX locations_area ON locations(area);
+";
+
+fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Boss => "boss",
+        Kind::MiniBoss => "mini_boss",
+        Kind::Normal => "normal"
+    }
+}
+
+// Writes everything into a fresh database in one transaction
+pub fn export(conn: &mut Connection, metadata: Option<&Metadata>, shadows: &[Shadow]) -> anyhow::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute_batch(SCHEMA)?;
+
+    if let Some(m) = metadata {
+        tx.execute(
+            "INSERT INTO metadata (game, generated_at, liz_version, wiki_url) VALUES (?1, ?2, ?3, ?4)",
+            params![m.game, m.generated_at, m.liz_version, m.wiki_url]
+        )?;
+    }
+
+    for shadow in shadows {
+        let infobox = shadow.infobox.clone().unwrap_or_default();
+        tx.execute(
+            "INSERT INTO shadows (name, kind, japanese_name, romaji, page_id, revision_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                shadow.name,
+                shadow.kind.map(kind_name),
+                infobox.japanese_name,
+                infobox.romaji,
+                shadow.source.as_ref().map(|s| s.page_id as i64),
+                shadow.source.as_ref().and_then(|s| s.revision_id).map(|r| r as i64)
+            ]
+        )?;
+        let shadow_id = tx.last_insert_rowid();
+
+        for arcana in &shadow.arcana {
+            tx.execute("INSERT INTO shadow_arcana (shadow_id, arcana) VALUES (?1, ?2)", params![shadow_id, arcana])?;
+        }
+
+        for location in &shadow.locations {
+            tx.execute(
+                "INSERT INTO locations (shadow_id, game, area, region, first_floor, last_floor) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    shadow_id,
+                    location.game,
+                    location.area,
+                    location.region,
+                    location.floors.map(|f| f.0),
+                    location.floors.map(|f| f.1)
+                ]
+            )?;
+        }
+
+        for info in &shadow.info {
+            let stats = info.stats.clone().unwrap_or_default();
+            tx.execute(
+                "INSERT INTO variants (shadow_id, game, name, level, hp, sp, strength, magic, endurance, agility, luck)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    shadow_id,
+                    info.game,
+                    info.variant,
+                    stats.level,
+                    stats.hp,
+                    stats.sp,
+                    stats.strength,
+                    stats.magic,
+                    stats.endurance,
+                    stats.agility,
+                    stats.luck
+                ]
+            )?;
+            let variant_id = tx.last_insert_rowid();
+
+            for (affinity, elements) in &info.resistances {
+                for element in elements {
+                    tx.execute(
+                        "INSERT INTO affinities (variant_id, element, affinity) VALUES (?1, ?2, ?3)",
+                        params![variant_id, element, affinity]
+                    )?;
+                }
+            }
+
+            for (position, skill) in info.skills.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO skills (variant_id, position, name, effect) VALUES (?1, ?2, ?3, ?4)",
+                    params![variant_id, position as i64, skill.name, skill.effect]
+                )?;
+            }
+        }
+    }
+
+    tx.commit()?;
+    Ok(())
+}
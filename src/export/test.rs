@@ -0,0 +1,69 @@
+use super::*;
+use crate::dump;
+
+fn exported() -> Connection {
+    let (_, shadows) = dump::parse(include_str!("../../shadows.json")).unwrap();
+    let mut conn = Connection::open_in_memory().unwrap();
+    export(&mut conn, None, &shadows).unwrap();
+    conn
+}
+
+#[test]
+fn export_counts() {
+    let (_, shadows) = dump::parse(include_str!("../../shadows.json")).unwrap();
+    let conn = exported();
+
+    let count = |sql: &str| conn.query_row(sql, [], |r| r.get::<_, i64>(0)).unwrap() as usize;
+    assert_eq!(count("SELECT COUNT(*) FROM shadows"), shadows.len());
+    assert_eq!(count("SELECT COUNT(*) FROM variants"), shadows.iter().map(|s| s.info.len()).sum::<usize>());
+    assert_eq!(count("SELECT COUNT(*) FROM metadata"), 0);
+}
+
+#[test]
+fn export_answer_variants_draining_fire() {
+    let conn = exported();
+
+    let mut statement = conn.prepare(
+        "SELECT DISTINCT s.name FROM shadows s
+         JOIN variants v ON v.shadow_id = s.id
+         JOIN affinities a ON a.variant_id = v.id
+         WHERE v.name = 'The Answer' AND a.element = 'Fire' AND a.affinity = 'Drain'"
+    ).unwrap();
+    let names: Vec<String> = statement.query_map([], |r| r.get(0)).unwrap().map(|n| n.unwrap()).collect();
+
+    assert!(names.contains(&"Crying Table".to_string()));
+}
+
+#[test]
+fn export_metadata_and_details() {
+    let (_, mut shadows) = dump::parse(include_str!("../../shadows.json")).unwrap();
+    shadows.retain(|s| s.name == "Crying Table");
+    shadows[0].info[0].stats = Some(crate::wikia::Stats {
+        level: Some(14),
+        ..Default::default()
+    });
+    shadows[0].info[0].skills = vec![crate::wikia::Skill {
+        name: "Maragi".to_string(),
+        effect: "Deals light Fire damage to all foes.".to_string()
+    }];
+    let metadata = Metadata {
+        game: "Persona 3".to_string(),
+        generated_at: "2020-08-25T19:47:12Z".to_string(),
+        liz_version: "0.1.0".to_string(),
+        wiki_url: "https://megamitensei.fandom.com".to_string()
+    };
+
+    let mut conn = Connection::open_in_memory().unwrap();
+    export(&mut conn, Some(&metadata), &shadows).unwrap();
+
+    let level: i64 = conn.query_row("SELECT level FROM variants WHERE name = 'Sub-Boss'", [], |r| r.get(0)).unwrap();
+    assert_eq!(level, 14);
+    let skill: String = conn.query_row(
+        "SELECT sk.name FROM skills sk JOIN variants v ON v.id = sk.variant_id WHERE v.name = 'Sub-Boss'",
+        [],
+        |r| r.get(0)
+    ).unwrap();
+    assert_eq!(skill, "Maragi");
+    let game: String = conn.query_row("SELECT game FROM metadata", [], |r| r.get(0)).unwrap();
+    assert_eq!(game, "Persona 3");
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use crate::errors::UsageError;
+use crate::wikia::IndexEntry;
+
+// (arcana, entries) in the order the index lists them
+pub fn group(entries: Vec<IndexEntry>, arcana: Option<&str>) -> anyhow::Result<Vec<(String, Vec<IndexEntry>)>> {
+    let mut groups: Vec<(String, Vec<IndexEntry>)> = vec![];
+    for entry in entries {
+        let name = entry.arcana.clone().unwrap_or_else(|| "Unsorted".to_string());
+        match groups.iter_mut().find(|(g, _)| *g == name) {
+            Some((_, group)) => group.push(entry),
+            None => groups.push((name, vec![entry]))
+        }
+    }
+
+    if let Some(wanted) = arcana {
+        let available: Vec<String> = groups.iter().map(|(g, _)| g.clone()).collect();
+        groups.retain(|(g, _)| g.eq_ignore_ascii_case(wanted.trim()));
+        if groups.is_empty() {
+            return Err(UsageError {
+                reason: format!("No shadows listed under {}, the index has: {}", wanted, available.join(", "))
+            }.into());
+        }
+    }
+
+    Ok(groups)
+}
+
+pub fn print_groups(groups: &[(String, Vec<IndexEntry>)]) {
+    let name_width = groups.iter()
+        .flat_map(|(_, entries)| entries.iter().map(|e| e.name.len()))
+        .max()
+        .unwrap_or(0);
+
+    for (arcana, entries) in groups {
+        println!("{}", arcana.bold());
+        for entry in entries {
+            let level = entry.level.as_ref().map(|l| format!("Lv {}", l)).unwrap_or_default();
+            println!(
+                "  {:name_width$}  {:6}  {}",
+                entry.name,
+                level,
+                entry.area.as_deref().unwrap_or("").dimmed(),
+                name_width = name_width
+            );
+        }
+        println!();
+    }
+}
@@ -0,0 +1,43 @@
+use super::*;
+use crate::errors;
+
+fn entry(name: &str, arcana: Option<&str>) -> IndexEntry {
+    IndexEntry {
+        name: name.to_string(),
+        arcana: arcana.map(|a| a.to_string()),
+        level: None,
+        area: None
+    }
+}
+
+fn entries() -> Vec<IndexEntry> {
+    vec![
+        entry("Cowardly Maya", Some("Magician")),
+        entry("Dancing Hand", Some("Priestess")),
+        entry("Crying Table", Some("Magician")),
+        entry("Nyx", None)
+    ]
+}
+
+#[test]
+fn groups_in_index_order() {
+    let groups = group(entries(), None).unwrap();
+    let names: Vec<(&str, usize)> = groups.iter().map(|(g, e)| (g.as_str(), e.len())).collect();
+
+    assert_eq!(names, vec![("Magician", 2), ("Priestess", 1), ("Unsorted", 1)]);
+}
+
+#[test]
+fn filters_arcana() {
+    let groups = group(entries(), Some("magician")).unwrap();
+
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].1.iter().map(|e| e.name.as_str()).collect::<Vec<&str>>(), vec!["Cowardly Maya", "Crying Table"]);
+}
+
+#[test]
+fn unknown_arcana() {
+    let error = group(entries(), Some("tower")).unwrap_err();
+    assert!(error.to_string().contains("Magician, Priestess"));
+    assert_eq!(errors::exit_code(&error), errors::EXIT_USAGE);
+}
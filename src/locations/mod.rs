@@ -0,0 +1,326 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use crate::wikia::{self, Game, PersonaTitle, Shadow, SkipReason, Skipped};
+
+// Somewhere a shadow shows up, e.g. floors 48 to 63 of Arqa Block
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Location {
+    pub game: String,
+    pub area: String,
+    // the larger place the area is part of, e.g. Tartarus for its blocks
+    pub region: String,
+    // first and last floor (or hall) the shadow appears on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub floors: Option<(u32, u32)>
+}
+
+#[derive(Debug)]
+pub struct LocationIndex {
+    pub shadows: Vec<(String, Vec<Location>)>,
+    // shadows whose pages couldn't be read, left out of the index
+    pub skipped: Vec<Skipped>
+}
+
+// (area, region) pairs, as they're written in Profile sections
+const P3_AREAS: &[(&str, &str)] = &[
+    ("Thebel Block", "Tartarus"),
+    ("Arqa Block", "Tartarus"),
+    ("Yabbashah Block", "Tartarus"),
+    ("Tziah Block", "Tartarus"),
+    ("Harabah Block", "Tartarus"),
+    ("Adamah Block", "Tartarus"),
+    ("Monad Block", "Tartarus"),
+    ("Monad Depths", "Tartarus"),
+    ("Tartarus", "Tartarus"),
+    ("Empyrean", "Abyss of Time"),
+    ("Lethe", "Abyss of Time"),
+    ("Gehenna", "Abyss of Time"),
+    ("Caina", "Abyss of Time"),
+    ("Antenora", "Abyss of Time"),
+    ("Ptolomea", "Abyss of Time"),
+    ("Judecca", "Abyss of Time"),
+    ("Abyss of Time", "Abyss of Time")
+];
+
+const P4_AREAS: &[(&str, &str)] = &[
+    ("Yukiko's Castle", "Midnight Channel"),
+    ("Steamy Bathhouse", "Midnight Channel"),
+    ("Marukyu Striptease", "Midnight Channel"),
+    ("Void Quest", "Midnight Channel"),
+    ("Secret Laboratory", "Midnight Channel"),
+    ("Heaven", "Midnight Channel"),
+    ("Magatsu Inaba", "Midnight Channel"),
+    ("Hollow Forest", "Midnight Channel"),
+    ("Yomotsu Hirasaka", "Midnight Channel"),
+    ("Magatsu Mandala", "Midnight Channel")
+];
+
+fn known_areas(game: &Game) -> &'static [(&'static str, &'static str)] {
+    match game.entry {
+        PersonaTitle::P3J | PersonaTitle::P3A => P3_AREAS,
+        PersonaTitle::P4G => P4_AREAS
+    }
+}
+
+// Profile sections are split up by h3 game headings ("Persona 3", "Persona 4 Golden"),
+// except for shadows appearing in a single game, where there's no heading at all
+pub fn profile_text(page: &Html, game: &Game) -> Option<String> {
+    let profile_selector = Selector::parse("#Profile-collapsible-section").unwrap();
+    let section = page.select(&profile_selector).next()?;
+
+    let has_headings = section.children()
+        .filter_map(ElementRef::wrap)
+        .any(|e| e.value().name() == "h3");
+
+    let mut in_game = !has_headings;
+    let mut text = String::new();
+    for child in section.children().filter_map(ElementRef::wrap) {
+        if child.value().name() == "h3" {
+            in_game = child.text().collect::<String>().trim().starts_with(&game.entry_text);
+            continue;
+        }
+
+        if in_game {
+            text += child.text().collect::<String>().trim();
+            text += " ";
+        }
+    }
+
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+// Profiles are prose, so this goes clause by clause: "...in the Arqa Block of Tartarus between
+// floors 48 to 63, and as a boss on the 8th floor of Ptolomea" is two locations
+pub fn parse_locations(profile: &str, game: &Game) -> Vec<Location> {
+    let mut locations: Vec<Location> = vec![];
+
+    let clauses = profile
+        .split(". ")
+        .flat_map(|sentence| sentence.split(", and "))
+        .flat_map(|clause| clause.split("; "));
+
+    for clause in clauses {
+        let lowered = clause.to_lowercase();
+
+        // the first match is the most specific, blocks come before Tartarus itself
+        let area = known_areas(game).iter().find(|(area, _)| lowered.contains(&area.to_lowercase()));
+        let (area, region) = match area {
+            Some(a) => a,
+            None => continue
+        };
+
+        let ranges = floor_ranges(clause);
+        let floors = match (ranges.iter().map(|r| r.0).min(), ranges.iter().map(|r| r.1).max()) {
+            (Some(first), Some(last)) => Some((first, last)),
+            _ => None
+        };
+
+        let location = Location {
+            game: game.entry_text.clone(),
+            area: area.to_string(),
+            region: region.to_string(),
+            floors
+        };
+        if !locations.contains(&location) {
+            locations.push(location);
+        }
+    }
+
+    locations
+}
+
+// A dump made with -a already carries locations, saving a trip through every shadow page.
+// Returns None for older dumps that predate locations
+pub fn index_from_dump(dump: &[Shadow]) -> Option<LocationIndex> {
+    if dump.iter().all(|s| s.locations.is_empty()) {
+        return None;
+    }
+
+    Some(LocationIndex {
+        shadows: dump.iter().map(|s| (s.name.clone(), s.locations.clone())).collect(),
+        skipped: vec![]
+    })
+}
+
+// Fetches every shadow page for the game, which the page cache makes cheap after the first time.
+// A page that can't be read is skipped like in a dump, the rest of the index is still useful
+pub fn build_index(game: &Game) -> anyhow::Result<LocationIndex> {
+    let mut shadows = vec![];
+    let mut skipped = vec![];
+
+    for name in wikia::shadow_names(game)? {
+        let mut skip = |reason: SkipReason, message: String| {
+            eprintln!("{}", message);
+            skipped.push(Skipped {
+                name: name.clone(),
+                reason,
+                message
+            });
+        };
+
+        let page_id = match wikia::get_shadow_page_id(&name) {
+            Ok(-1) => {
+                skip(SkipReason::NotFound, format!("No wiki page for: {}", name));
+                continue;
+            },
+            Ok(id) => id,
+            Err(e) => {
+                skip(SkipReason::of(&e), e.to_string());
+                continue;
+            }
+        };
+
+        let page = match wikia::page_html(&page_id) {
+            Ok(page) => page,
+            Err(e) => {
+                skip(SkipReason::of(&e), e.to_string());
+                continue;
+            }
+        };
+        if let Some(text) = profile_text(&page, game) {
+            shadows.push((name, parse_locations(&text, game)));
+        }
+    }
+
+    Ok(LocationIndex { shadows, skipped })
+}
+
+// numbers and "x to y" ranges next to floor or hall wording, reading "25th" as 25 and "50F"
+// as floor 50. Other numbers in the clause ("Selling 10 of these") aren't floors
+fn floor_ranges(sentence: &str) -> Vec<(u32, u32)> {
+    let words: Vec<String> = sentence
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '-')
+        .map(|w| w.trim_end_matches(|c: char| !c.is_ascii_digit() && !c.is_alphabetic()).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    // the number, and whether it's written as a floor itself
+    let number = |word: &str| -> Option<(u32, bool)> {
+        let digits: String = word.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let suffix = &word[digits.len()..];
+        match suffix {
+            "" | "st" | "nd" | "rd" | "th" => digits.parse().ok().map(|n| (n, false)),
+            "f" => digits.parse().ok().map(|n| (n, true)),
+            _ => None
+        }
+    };
+    let floor_word = |idx: Option<usize>| idx
+        .and_then(|i| words.get(i))
+        .is_some_and(|w| matches!(w.as_str(), "floor" | "floors" | "hall" | "halls" | "f"));
+
+    // (first word, last word, range, next to floor wording)
+    let mut candidates: Vec<(usize, usize, (u32, u32), bool)> = vec![];
+    let mut idx = 0;
+    while idx < words.len() {
+        if let Some((start, is_floor)) = number(&words[idx]) {
+            let to = words.get(idx + 1).is_some_and(|w| w == "to" || w == "through");
+            let last = match words.get(idx + 2).and_then(|w| number(w)) {
+                Some((end, end_is_floor)) if to => Some((idx + 2, end, end_is_floor)),
+                _ => None
+            };
+            let (last_idx, end, end_is_floor) = last.unwrap_or((idx, start, false));
+
+            let near_floor = is_floor || end_is_floor || floor_word(idx.checked_sub(1)) || floor_word(Some(last_idx + 1));
+            candidates.push((idx, last_idx, (start, end), near_floor));
+            idx = last_idx;
+        }
+        idx += 1;
+    }
+
+    // "the 9th and 10th Halls": a number listed along with a floor is one too
+    for i in (0..candidates.len().saturating_sub(1)).rev() {
+        let (_, last_idx, _, near_floor) = candidates[i];
+        let (next_idx, _, _, next_near_floor) = candidates[i + 1];
+        let joined = next_idx == last_idx + 2 && matches!(words[last_idx + 1].as_str(), "and" | "or");
+        if joined && !near_floor && next_near_floor {
+            candidates[i].3 = true;
+        }
+    }
+
+    candidates.into_iter()
+        .filter(|c| c.3)
+        .map(|c| c.2)
+        .collect()
+}
+
+impl Location {
+    // "Tartarus" matches every block, "arqa" just the one
+    pub fn matches(&self, area: &str, floor: Option<u32>) -> bool {
+        let area = area.to_lowercase();
+        let in_area = self.area.to_lowercase().contains(&area) || self.region.to_lowercase() == area;
+
+        in_area && match (floor, self.floors) {
+            (Some(f), Some((first, last))) => first <= f && f <= last,
+            (Some(_), None) => false,
+            (None, _) => true
+        }
+    }
+}
+
+impl LocationIndex {
+    // names of the shadows appearing in the area, and on the floor if one's given
+    pub fn in_area(&self, area: &str, floor: Option<u32>) -> Vec<String> {
+        self.shadows.iter()
+            .filter(|(_, locations)| locations.iter().any(|l| l.matches(area, floor)))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+fn floors_text(floors: Option<(u32, u32)>) -> String {
+    match floors {
+        Some((first, last)) if first == last => format!(" floor {}", first),
+        Some((first, last)) => format!(" floors {}-{}", first, last),
+        None => "".to_string()
+    }
+}
+
+pub fn print_locations(name: &str, locations: &[Location]) {
+    println!("{}", name);
+    println!();
+
+    if locations.is_empty() {
+        println!("No locations found in its Profile");
+    }
+    for location in locations {
+        println!("{} ({}){}", location.area.green(), location.region, floors_text(location.floors));
+    }
+}
+
+// the index is missing whatever couldn't be read, say so under the results
+pub fn print_skipped(index: &LocationIndex) {
+    if index.skipped.is_empty() {
+        return;
+    }
+
+    let names: Vec<&str> = index.skipped.iter().map(|s| s.name.as_str()).collect();
+    eprintln!("{} {} shadows couldn't be read and aren't included: {}", "warning:".yellow(), names.len(), names.join(", "));
+}
+
+pub fn print_area(index: &LocationIndex, area: &str, floor: Option<u32>) {
+    let mut found = 0;
+
+    for (name, locations) in &index.shadows {
+        let matching: Vec<String> = locations.iter()
+            .filter(|l| l.matches(area, floor))
+            .map(|l| format!("{}{}", l.area, floors_text(l.floors)))
+            .collect();
+
+        if !matching.is_empty() {
+            found += 1;
+            println!("{} {}", name, format!("({})", matching.join(", ")).dimmed());
+        }
+    }
+
+    println!();
+    println!("{} shadow(s) found", found);
+}
@@ -0,0 +1,80 @@
+use super::*;
+use crate::utils;
+
+fn crying_table() -> Html {
+    wikia::parse_page(include_str!("../../fixtures/crying_table.json")).unwrap()
+}
+
+#[test]
+fn profile_text_per_game() {
+    let page = crying_table();
+
+    let p3 = profile_text(&page, &utils::determine_game("3")).unwrap();
+    assert!(p3.contains("Arqa Block"));
+    assert!(!p3.contains("Steamy Bathhouse"));
+
+    let p4 = profile_text(&page, &utils::determine_game("4")).unwrap();
+    assert!(p4.contains("Steamy Bathhouse"));
+    assert!(!p4.contains("Tartarus"));
+}
+
+#[test]
+fn floor_ranges_ordinals_and_ranges() {
+    assert_eq!(floor_ranges("found on the 25th floor of Tartarus"), vec![(25, 25)]);
+    assert_eq!(floor_ranges("in the Arqa Block between floors 48 to 63"), vec![(48, 63)]);
+    assert_eq!(floor_ranges("It is found in Halls 8 to 10 of Steamy Bathhouse."), vec![(8, 10)]);
+    assert_eq!(floor_ranges("the 9th and 10th Halls"), vec![(9, 9), (10, 10)]);
+    assert_eq!(floor_ranges("Arqa 50F"), vec![(50, 50)]);
+}
+
+#[test]
+fn floor_ranges_skip_other_numbers() {
+    assert_eq!(floor_ranges("Three Crying Table shadows act as a sub-boss found on the 25th floor"), vec![(25, 25)]);
+    assert_eq!(floor_ranges("Selling 10 of these to Daidara will unlock the Bravery Vessel"), vec![]);
+    assert_eq!(floor_ranges("find her a Reflecting Board for Quest #12 on 2 floors"), vec![(2, 2)]);
+}
+
+#[test]
+fn parse_locations_p3() {
+    let game = utils::determine_game("3");
+    let locations = parse_locations(&profile_text(&crying_table(), &game).unwrap(), &game);
+
+    let areas: Vec<(&str, Option<(u32, u32)>)> = locations.iter()
+        .map(|l| (l.area.as_str(), l.floors))
+        .collect();
+    assert_eq!(areas, vec![
+        ("Arqa Block", Some((25, 25))),
+        ("Arqa Block", Some((48, 63))),
+        ("Ptolomea", Some((8, 8)))
+    ]);
+    assert!(locations.iter().all(|l| l.game == "Persona 3"));
+    assert_eq!(locations[0].region, "Tartarus");
+}
+
+#[test]
+fn parse_locations_p4() {
+    let game = utils::determine_game("4");
+    let locations = parse_locations(&profile_text(&crying_table(), &game).unwrap(), &game);
+
+    assert!(!locations.is_empty());
+    assert!(locations.iter().all(|l| l.area == "Steamy Bathhouse"));
+    assert_eq!(locations[0].floors, Some((9, 10)));
+}
+
+#[test]
+fn in_area_by_region_and_floor() {
+    let game = utils::determine_game("3");
+    let index = LocationIndex {
+        shadows: vec![(
+            "Crying Table".to_string(),
+            parse_locations(&profile_text(&crying_table(), &game).unwrap(), &game)
+        )],
+        skipped: vec![]
+    };
+
+    assert_eq!(index.in_area("arqa block", None), vec!["Crying Table".to_string()]);
+    assert_eq!(index.in_area("tartarus", Some(50)), vec!["Crying Table".to_string()]);
+    assert!(index.in_area("arqa block", Some(8)).is_empty());
+    assert!(index.in_area("arqa block", Some(70)).is_empty());
+    assert!(index.in_area("yabbashah", None).is_empty());
+}
@@ -1,10 +1,32 @@
+mod batch;
+mod browse;
+mod cache;
+mod compare;
+mod completions;
+mod config;
+mod coverage;
+mod diff;
+mod dump;
 mod errors;
+mod export;
+mod list;
+mod locations;
+mod plan;
+mod search;
+mod serve;
+mod shell;
+mod source;
 mod utils;
+mod verify;
 mod wikia;
 
 use argh::FromArgs;
 use inflector::Inflector;
-use crate::wikia::Shadow;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
 
 #[derive(FromArgs)]
 /// Find shadow resistance/weakness information
@@ -16,54 +38,812 @@ struct Opts {
     /// persona series number.
     /// One of: 3, 4
     #[argh(option, short = 'p')]
-    persona: String,
+    persona: Option<String>,
 
     /// get all shadow resistance info for specified game.
     /// Defaults to false
     #[argh(switch, short = 'a')]
+    all: bool,
+
+    /// also print the japanese name, arcana and other infobox details
+    #[argh(switch, short = 'v')]
+    verbose: bool,
+
+    /// print which page layouts were tried, what matched and the html
+    /// considered to stderr, for filing bug reports about wiki pages;
+    /// single shadows only, not with -a or --wikitext
+    #[argh(switch)]
+    debug_parse: bool,
+
+    /// read resistances from the stat templates in the page's wikitext
+    /// instead of the rendered html
+    #[argh(switch)]
+    wikitext: bool,
+
+    /// wiki to fetch pages from, e.g. a mirror or a local server. Defaults
+    /// to $LIZ_WIKI_URL, then the config file, then
+    /// https://megamitensei.fandom.com
+    #[argh(option)]
+    wiki_url: Option<String>,
+
+    /// with -a, print a bare array of shadows without the metadata, like
+    /// older versions did
+    #[argh(switch)]
+    bare: bool,
+
+    /// with -a, only dump shadows of this kind.
+    /// One of: boss, mini-boss, normal
+    #[argh(option, short = 'k')]
+    kind: Option<String>,
+
+    #[argh(subcommand)]
+    command: Option<Command>
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    Search(SearchOpts),
+    Compare(CompareOpts),
+    Plan(PlanOpts),
+    Coverage(CoverageOpts),
+    Where(WhereOpts),
+    Area(AreaOpts),
+    Diff(DiffOpts),
+    Verify(VerifyOpts),
+    Export(ExportOpts),
+    Serve(ServeOpts),
+    Shell(ShellOpts),
+    Browse(BrowseOpts),
+    Completions(CompletionsOpts),
+    List(ListOpts),
+    Lookup(LookupOpts)
+}
+
+#[derive(FromArgs)]
+/// List shadows whose affinities match a query.
+/// Queries are affinity:element terms combined with and/or/not,
+/// e.g. "weak:elec and not (repel:fire or drain:fire)"
+#[argh(subcommand, name = "search")]
+struct SearchOpts {
+    /// query expression
+    #[argh(positional)]
+    query: Option<String>,
+
+    /// element the shadow must be weak to. May be repeated
+    #[argh(option)]
+    weak: Vec<String>,
+
+    /// element the shadow must be immune (null) to. May be repeated
+    #[argh(option)]
+    immune: Vec<String>,
+
+    /// part of the shadow's name, in english, japanese or romaji
+    #[argh(option)]
+    name: Option<String>,
+
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// search a dump created with -a instead of fetching every shadow page
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Show the affinities of several shadows side by side, each in the game's usual
+/// variant. Pick another with "name:variant", e.g. "crying table:answer"
+#[argh(subcommand, name = "compare")]
+struct CompareOpts {
+    /// shadows to compare
+    #[argh(positional)]
+    shadows: Vec<String>,
+
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// look shadows up in a dump created with -a instead of the wiki
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Rank elements against a group of enemies.
+/// Give counts with "name x3" and variants with "name:variant"
+#[argh(subcommand, name = "plan")]
+struct PlanOpts {
+    /// enemies in the encounter
+    #[argh(positional)]
+    enemies: Vec<String>,
+
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// look shadows up in a dump created with -a instead of the wiki
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Check a party's elements against the shadows of an area.
+/// Areas are matched against each shadow's Profile, e.g. "arqa block" or "steamy bathhouse"
+#[argh(subcommand, name = "coverage")]
+struct CoverageOpts {
+    /// the party's elements, e.g. "fire, elec, pierce, ice"
+    #[argh(option)]
+    party: String,
+
+    /// tartarus block, dungeon, or any other place name
+    #[argh(option)]
+    area: String,
+
+    /// only shadows appearing on this floor of the area
+    #[argh(option)]
+    floor: Option<u32>,
+
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// take affinities from a dump created with -a instead of the wiki
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Show where a shadow appears, according to its Profile
+#[argh(subcommand, name = "where")]
+struct WhereOpts {
+    /// name of shadow
+    #[argh(positional)]
+    shadow: String,
+
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// look the shadow up in a dump created with -a instead of the wiki
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// List the shadows appearing in a dungeon, block or region
+#[argh(subcommand, name = "area")]
+struct AreaOpts {
+    /// e.g. "arqa block", "tartarus" or "steamy bathhouse"
+    #[argh(positional)]
+    area: String,
+
+    /// only shadows appearing on this floor
+    #[argh(option)]
+    floor: Option<u32>,
+
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// use the locations in a dump created with -a instead of the wiki
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Report shadows, variants and affinities that changed between two dumps.
+/// Exits with 1 when there are differences
+#[argh(subcommand, name = "diff")]
+struct DiffOpts {
+    /// the older dump
+    #[argh(positional)]
+    old: String,
+
+    /// the newer dump
+    #[argh(positional)]
+    new: String,
+
+    /// print the differences as json
+    #[argh(switch)]
+    json: bool
+}
+
+#[derive(FromArgs)]
+/// Scrape shadows from a reference dump again and report any that no longer match.
+/// Exits with 1 on mismatches
+#[argh(subcommand, name = "verify")]
+struct VerifyOpts {
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// reference dump. Defaults to shadows.json
+    #[argh(option, short = 'f', default = "String::from(\"shadows.json\")")]
+    file: String,
+
+    /// how many shadows to check, spread across the dump. Defaults to 10
+    #[argh(option, default = "10")]
+    sample: usize,
+
+    /// check every shadow in the dump
+    #[argh(switch, short = 'a')]
     all: bool
 }
 
-fn main() -> anyhow::Result<()> {
-    let opts: Opts = argh::from_env();
-    let game = utils::determine_game(&opts.persona.as_str());
+#[derive(FromArgs)]
+/// Write a game's shadows, variants, affinities, stats and skills to a SQLite database
+#[argh(subcommand, name = "export")]
+struct ExportOpts {
+    /// database file to create
+    #[argh(option, short = 'o')]
+    output: String,
+
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// export a dump created with -a instead of fetching every shadow page
+    #[argh(option, short = 'f')]
+    file: Option<String>,
+
+    /// replace the database if it already exists
+    #[argh(switch)]
+    force: bool
+}
+
+#[derive(FromArgs)]
+/// Serve shadow lookups and searches as JSON over HTTP.
+/// Endpoints: /games, /games/<game>/shadows, /games/<game>/shadows/<name>,
+/// /search?game=3&weak=fire
+#[argh(subcommand, name = "serve")]
+struct ServeOpts {
+    /// port to listen on. Defaults to 8080
+    #[argh(option, default = "8080")]
+    port: u16,
+
+    /// address to listen on. Defaults to 127.0.0.1
+    #[argh(option, default = "String::from(\"127.0.0.1\")")]
+    host: String,
+
+    /// serve a dump created with -a instead of the wiki
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Look shadows up interactively, keeping fetched pages in memory.
+/// Switch games with "game 4g", "help" lists the commands
+#[argh(subcommand, name = "shell")]
+struct ShellOpts {
+    /// persona series number to start with. Defaults to 3
+    #[argh(option, short = 'p', default = "String::from(\"3\")")]
+    persona: String,
+
+    /// look shadows up in a dump created with -a instead of the wiki
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Browse a game's shadows in a full-screen terminal UI
+#[argh(subcommand, name = "browse")]
+struct BrowseOpts {
+    /// persona series number. Defaults to 3
+    #[argh(option, short = 'p', default = "String::from(\"3\")")]
+    persona: String,
+
+    /// browse a dump created with -a instead of the wiki
+    #[argh(option, short = 'f')]
+    file: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Print a completion script, e.g. source <(liz completions bash)
+#[argh(subcommand, name = "completions")]
+struct CompletionsOpts {
+    /// bash, zsh or fish. "names" prints the shadow names the scripts complete with
+    #[argh(positional)]
+    shell: String,
+
+    /// persona series number the names are for. Defaults to 3
+    #[argh(option, short = 'p', default = "String::from(\"3\")")]
+    persona: String
+}
+
+#[derive(FromArgs)]
+/// List a game's shadows by arcana from its index page, without fetching every shadow
+#[argh(subcommand, name = "list")]
+struct ListOpts {
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// only list shadows of this arcana, e.g. magician
+    #[argh(option)]
+    arcana: Option<String>
+}
+
+#[derive(FromArgs)]
+/// Look up several shadows at once, one name per line.
+/// Lookups that fail are listed at the end instead of stopping the rest
+#[argh(subcommand, name = "lookup")]
+struct LookupOpts {
+    /// file with the shadow names. Reads stdin when missing or "-"
+    #[argh(option)]
+    from: Option<String>,
+
+    /// persona series number.
+    /// One of: 3, 4
+    #[argh(option, short = 'p')]
+    persona: String,
+
+    /// how many shadows to fetch at once. Defaults to 4
+    #[argh(option, default = "4")]
+    jobs: usize,
+
+    /// print the shadows as a dump instead of their resistances
+    #[argh(switch)]
+    json: bool
+}
+
+fn main() {
+    let opts = parse_args();
+
+    let code = match run(opts) {
+        Ok(()) => errors::EXIT_OK,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            errors::exit_code(&e)
+        }
+    };
+    process::exit(code);
+}
+
+// argh::from_env, but exiting with the usage code on bad arguments
+fn parse_args() -> Opts {
+    let args: Vec<String> = env::args().collect();
+    let command = args.first()
+        .and_then(|a| Path::new(a).file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("liz");
+    let strs: Vec<&str> = args.iter().skip(1).map(|a| a.as_str()).collect();
+
+    Opts::from_args(&[command], &strs).unwrap_or_else(|early_exit| match early_exit.status {
+        Ok(()) => {
+            println!("{}", early_exit.output);
+            process::exit(errors::EXIT_OK);
+        },
+        Err(()) => {
+            eprintln!("{}\nRun {} --help for more information.", early_exit.output, command);
+            process::exit(errors::EXIT_USAGE);
+        }
+    })
+}
+
+fn run(opts: Opts) -> anyhow::Result<()> {
+    config::init(opts.wiki_url.as_deref());
+
+    match opts.command {
+        Some(Command::Search(search_opts)) => search(search_opts),
+        Some(Command::Compare(compare_opts)) => compare(compare_opts),
+        Some(Command::Plan(plan_opts)) => plan(plan_opts),
+        Some(Command::Coverage(coverage_opts)) => coverage(coverage_opts),
+        Some(Command::Where(where_opts)) => where_is(where_opts),
+        Some(Command::Area(area_opts)) => area(area_opts),
+        Some(Command::Diff(diff_opts)) => diff(diff_opts),
+        Some(Command::Verify(verify_opts)) => verify(verify_opts),
+        Some(Command::Export(export_opts)) => export(export_opts),
+        Some(Command::Serve(serve_opts)) => serve(serve_opts),
+        Some(Command::Shell(shell_opts)) => shell(shell_opts),
+        Some(Command::Browse(browse_opts)) => browse(browse_opts),
+        Some(Command::Completions(completions_opts)) => completions(completions_opts),
+        Some(Command::List(list_opts)) => list(list_opts),
+        Some(Command::Lookup(lookup_opts)) => batch_lookup(lookup_opts),
+        None => lookup(opts)
+    }
+}
+
+fn lookup(opts: Opts) -> anyhow::Result<()> {
+    let persona = match opts.persona {
+        Some(p) => p,
+        None => return Err(errors::UsageError {
+            reason: "Required options not provided:\n    --persona".to_string()
+        }.into())
+    };
+    let game = utils::determine_game(persona.as_str());
+    let backend = if opts.wikitext { wikia::Backend::Wikitext } else { wikia::Backend::Html };
+    // the diagnostics only read a single shadow's HTML page
+    if opts.debug_parse && (opts.all || opts.wikitext) {
+        return Err(errors::UsageError {
+            reason: "--debug-parse can't be combined with --all or --wikitext".to_string()
+        }.into());
+    }
 
     if opts.all {
-        let all_shadow_info = wikia::arcana_sections(&game)?;
-        println!("{}", serde_json::to_string(&all_shadow_info)?);
+        let (mut all_shadow_info, skipped) = wikia::arcana_sections_with(&game, backend)?;
+        wikia::print_skipped(&skipped);
+        if let Some(kind) = &opts.kind {
+            let kind = match utils::parse_kind(kind) {
+                Some(k) => k,
+                None => return Err(errors::UsageError {
+                    reason: format!("Unknown kind: {}, expected one of: boss, mini-boss, normal", kind)
+                }.into())
+            };
+            all_shadow_info.retain(|s| s.kind == Some(kind));
+        }
+        if opts.bare {
+            println!("{}", serde_json::to_string(&all_shadow_info)?);
+        } else {
+            let mut dump = dump::Dump::new(&game, all_shadow_info);
+            dump.skipped = skipped;
+            println!("{}", serde_json::to_string(&dump)?);
+        }
     } else {
-        let page_id = wikia::get_shadow_page_id(&opts.shadow)?;
+        if opts.debug_parse {
+            let page_id = wikia::get_shadow_page_id(&opts.shadow)?;
+            if page_id == -1 {
+                eprintln!("no page found for {}", opts.shadow);
+            } else {
+                let page = wikia::page_html(&page_id)?;
+                let report = wikia::diagnostics::diagnose(&page, &game, &opts.shadow.to_title_case());
+                wikia::diagnostics::print_report(&report);
+            }
+        }
 
-        if page_id == -1 {
-            return Err(errors::NoShadowError {
-                name: opts.shadow.to_title_case(),
-                game: game.entry_text
-            }.into());
+        let shadow = wikia::get_shadow_with(&opts.shadow, &game, backend)?;
+        if opts.verbose {
+            utils::print_infobox(&shadow);
         }
+        utils::print_resistances(&shadow);
+    }
+
+    Ok(())
+}
+
+fn search(opts: SearchOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+
+    let mut queries = vec![];
+    if let Some(q) = &opts.query {
+        queries.push(search::parse(q)?);
+    }
+    for element in &opts.weak {
+        queries.push(search::Query::affinity("weak", element)?);
+    }
+    for element in &opts.immune {
+        queries.push(search::Query::affinity("null", element)?);
+    }
+    if let Some(name) = &opts.name {
+        queries.push(search::Query::Name(name.to_lowercase()));
+    }
+
+    let query = match search::Query::all(queries) {
+        Some(q) => q,
+        None => return Err(errors::UsageError {
+            reason: "Nothing to search for, provide a query or --weak/--immune/--name".to_string()
+        }.into())
+    };
+
+    let shadows = match &opts.file {
+        Some(path) => utils::read_dump(path, &game)?,
+        None => wikia::arcana_sections(&game)?
+    };
 
-        let page = wikia::page_html(&page_id)?;
-        let mut shadow = Shadow {
-            name: opts.shadow.to_title_case(),
-            info: vec![],
+    search::print_matches(&search::search(&shadows, &query));
+
+    Ok(())
+}
+
+fn compare(opts: CompareOpts) -> anyhow::Result<()> {
+    if opts.shadows.is_empty() {
+        return Err(errors::UsageError {
+            reason: "Nothing to compare, provide at least one shadow name".to_string()
+        }.into());
+    }
+
+    let game = utils::determine_game(opts.persona.as_str());
+    let dump = match &opts.file {
+        Some(path) => Some(utils::read_dump(path, &game)?),
+        None => None
+    };
+
+    let mut shadows = vec![];
+    for target in &opts.shadows {
+        let (name, variant) = compare::parse_target(target);
+        let mut shadow = match &dump {
+            Some(d) => utils::find_shadow(d, &name, &game)?,
+            None => wikia::get_shadow(&name, &game)?
         };
-        let appears_in = wikia::appears_in(&page, &game)?;
-        if !appears_in {
-            return Err(errors::NoShadowError {
-                name: shadow.name,
-                game: game.entry_text
-            }.into());
+
+        compare::retain_variant(&mut shadow, &variant, &game)?;
+        shadows.push(shadow);
+    }
+
+    compare::print_comparison(&compare::compare(&shadows, &utils::game_elements(&game)));
+
+    Ok(())
+}
+
+fn plan(opts: PlanOpts) -> anyhow::Result<()> {
+    if opts.enemies.is_empty() {
+        return Err(errors::UsageError {
+            reason: "Nothing to plan for, provide at least one enemy".to_string()
+        }.into());
+    }
+
+    let game = utils::determine_game(opts.persona.as_str());
+    let dump = match &opts.file {
+        Some(path) => Some(utils::read_dump(path, &game)?),
+        None => None
+    };
+
+    let mut enemies = vec![];
+    for target in &opts.enemies {
+        let (name, variant, count) = plan::parse_enemy(target);
+        let mut shadow = match &dump {
+            Some(d) => utils::find_shadow(d, &name, &game)?,
+            None => wikia::get_shadow(&name, &game)?
+        };
+
+        compare::retain_variant(&mut shadow, &variant, &game)?;
+        let info = match utils::preferred_info(&shadow, &game) {
+            Some(i) => i.clone(),
+            None => return Err(errors::NoVariantError {
+                shadow_name: shadow.name,
+                game: game.entry_text,
+                variant: variant.into_iter().collect()
+            }.into())
+        };
+
+        enemies.push(plan::Enemy {
+            name: shadow.name,
+            count,
+            info
+        });
+    }
+
+    let rules = plan::rules(&game);
+    plan::print_plan(&plan::plan(&enemies, &rules), &rules);
+
+    Ok(())
+}
+
+fn coverage(opts: CoverageOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+    let party = coverage::parse_party(&opts.party);
+    if party.is_empty() {
+        return Err(errors::UsageError {
+            reason: "The party needs at least one element".to_string()
+        }.into());
+    }
+
+    let dump = match &opts.file {
+        Some(path) => Some(utils::read_dump(path, &game)?),
+        None => None
+    };
+
+    let index = match dump.as_deref().and_then(locations::index_from_dump) {
+        Some(i) => i,
+        None => locations::build_index(&game)?
+    };
+    let names = index.in_area(&opts.area, opts.floor);
+
+    let mut shadows = vec![];
+    for name in &names {
+        let shadow = match &dump {
+            Some(d) => utils::find_shadow(d, name, &game),
+            None => wikia::get_shadow(name, &game)
+        };
+
+        match shadow {
+            Ok(s) => shadows.push(s),
+            Err(e) => eprintln!("{}", e)
         }
+    }
+
+    let area = match opts.floor {
+        Some(floor) => format!("{} (floor {})", opts.area, floor),
+        None => opts.area.clone()
+    };
+    coverage::print_report(&coverage::analyze(&shadows, &party, &game), &area);
+    locations::print_skipped(&index);
+
+    Ok(())
+}
 
-        let subsection = wikia::game_section(&page, &game, shadow.name.clone())?;
+fn where_is(opts: WhereOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+    let shadow = match &opts.file {
+        Some(path) => utils::find_shadow(&utils::read_dump(path, &game)?, &opts.shadow, &game)?,
+        None => wikia::get_shadow(&opts.shadow, &game)?
+    };
+
+    locations::print_locations(&shadow.name, &shadow.locations);
+
+    Ok(())
+}
 
-        let table_nodes = wikia::game_table(&subsection)?;
+fn area(opts: AreaOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+    let dump = match &opts.file {
+        Some(path) => Some(utils::read_dump(path, &game)?),
+        None => None
+    };
+
+    let index = match dump.as_deref().and_then(locations::index_from_dump) {
+        Some(i) => i,
+        None => locations::build_index(&game)?
+    };
+    locations::print_area(&index, &opts.area, opts.floor);
+    locations::print_skipped(&index);
+
+    Ok(())
+}
+
+fn diff(opts: DiffOpts) -> anyhow::Result<()> {
+    let (_, old) = dump::parse(&fs::read_to_string(&opts.old)?)?;
+    let (_, new) = dump::parse(&fs::read_to_string(&opts.new)?)?;
+
+    let result = diff::diff(&old, &new);
+    if opts.json {
+        println!("{}", serde_json::to_string(&result)?);
+    } else {
+        diff::print_diff(&result);
+    }
 
-        for (table, variant) in table_nodes {
-            shadow.info.push(wikia::extract_table_data(&table, &variant, &game)?);
+    if !result.is_empty() {
+        process::exit(errors::EXIT_DIFFERENCES);
+    }
+
+    Ok(())
+}
+
+fn verify(opts: VerifyOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+    let reference = utils::read_dump(&opts.file, &game)?;
+
+    let sample_size = if opts.all { reference.len() } else { opts.sample };
+    let shadows = verify::sample(&reference, sample_size);
+    let report = verify::verify(&shadows, |name| {
+        let mut shadow = wikia::get_shadow(name, &game)?;
+        shadow.info.retain(|i| i.game == game.entry_text);
+        Ok(shadow)
+    });
+    verify::print_report(&report);
+
+    if report.mismatches() > 0 {
+        process::exit(errors::EXIT_DIFFERENCES);
+    }
+
+    Ok(())
+}
+
+fn export(opts: ExportOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+
+    if std::path::Path::new(&opts.output).exists() && !opts.force {
+        return Err(errors::UsageError {
+            reason: format!("{} already exists, pass --force to replace it", opts.output)
+        }.into());
+    }
+
+    let (metadata, shadows) = match &opts.file {
+        Some(path) => {
+            let (metadata, shadows) = dump::parse(&fs::read_to_string(path)?)?;
+            (metadata, utils::for_game(shadows, &game))
+        },
+        None => {
+            let dump = dump::Dump::new(&game, wikia::arcana_sections(&game)?);
+            (Some(dump.metadata), dump.shadows)
         }
+    };
 
-        utils::print_resistances(&shadow);
+    // the old database is only replaced once the new one is complete
+    let temp = format!("{}.tmp", opts.output);
+    if std::path::Path::new(&temp).exists() {
+        fs::remove_file(&temp)?;
     }
+    let exported = rusqlite::Connection::open(&temp)
+        .map_err(anyhow::Error::from)
+        .and_then(|mut conn| export::export(&mut conn, metadata.as_ref(), &shadows));
+    if let Err(e) = exported {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    fs::rename(&temp, &opts.output)?;
+    println!("Exported {} shadow(s) to {}", shadows.len(), opts.output);
+
+    Ok(())
+}
+
+fn serve(opts: ServeOpts) -> anyhow::Result<()> {
+    let address = format!("{}:{}", opts.host, opts.port);
+
+    match &opts.file {
+        Some(path) => {
+            let (_, shadows) = dump::parse(&fs::read_to_string(path)?)?;
+            serve::serve(&address, &source::Dump { shadows })
+        },
+        None => serve::serve(&address, &source::Wiki::new())
+    }
+}
+
+fn shell(opts: ShellOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+
+    match &opts.file {
+        Some(path) => {
+            let (_, shadows) = dump::parse(&fs::read_to_string(path)?)?;
+            shell::shell(&source::Dump { shadows }, game)
+        },
+        None => shell::shell(&source::Wiki::new(), game)
+    }
+}
+
+fn browse(opts: BrowseOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+
+    match &opts.file {
+        Some(path) => {
+            let (_, shadows) = dump::parse(&fs::read_to_string(path)?)?;
+            browse::browse(&source::Dump { shadows }, game)
+        },
+        None => browse::browse(&source::Wiki::new(), game)
+    }
+}
 
+fn completions(opts: CompletionsOpts) -> anyhow::Result<()> {
+    if opts.shell == "names" {
+        for name in wikia::cached_shadow_names(&utils::determine_game(opts.persona.as_str()))? {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    print!("{}", completions::script(&opts.shell)?);
+    Ok(())
+}
+
+fn list(opts: ListOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+    let groups = list::group(wikia::shadow_index(&game)?, opts.arcana.as_deref())?;
+
+    list::print_groups(&groups);
+    Ok(())
+}
+
+fn batch_lookup(opts: LookupOpts) -> anyhow::Result<()> {
+    let game = utils::determine_game(opts.persona.as_str());
+    let text = match opts.from.as_deref() {
+        None | Some("-") => io::read_to_string(io::stdin())?,
+        Some(path) => fs::read_to_string(path)?
+    };
+    let names = batch::read_names(&text);
+
+    let (shadows, failures) = batch::lookup_all(&names, opts.jobs, |name| wikia::get_shadow(name, &game));
+    if opts.json {
+        let mut dump = dump::Dump::new(&game, shadows);
+        dump.skipped = batch::skipped(&failures);
+        println!("{}", serde_json::to_string(&dump)?);
+    } else {
+        for shadow in &shadows {
+            utils::print_resistances(shadow);
+        }
+    }
+    batch::print_failures(&failures, names.len());
+
+    if !failures.is_empty() {
+        process::exit(batch::exit_code(&failures));
+    }
     Ok(())
 }
@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use crate::utils;
+use crate::wikia::{Game, PersonaTitle, ShadowInfo};
+
+// The parts of the battle system that differ between games
+#[derive(Debug)]
+pub struct Rules {
+    pub elements: Vec<&'static str>,
+    // physical attacks can crit, which knocks down just like hitting a weakness
+    pub crit_elements: Vec<&'static str>,
+    // hitting a knocked down enemy's weakness again
+    pub downed_hit: &'static str
+}
+
+#[derive(Debug, Clone)]
+pub struct Enemy {
+    pub name: String,
+    pub count: usize,
+    pub info: ShadowInfo
+}
+
+#[derive(Debug)]
+pub struct ElementScore {
+    pub element: String,
+    pub score: isize,
+    // One More opportunities, counting every copy of an enemy
+    pub knockdowns: usize,
+    // enemies that repel or drain the element, the reason to avoid it
+    pub punishers: Vec<String>
+}
+
+#[derive(Debug)]
+pub struct Step {
+    pub element: String,
+    pub targets: Vec<String>
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    pub scores: Vec<ElementScore>,
+    pub all_out: Vec<Step>,
+    // enemies with no weakness, only crits will knock these down
+    pub unexploitable: Vec<String>
+}
+
+pub fn rules(game: &Game) -> Rules {
+    match game.entry {
+        PersonaTitle::P3J | PersonaTitle::P3A => Rules {
+            elements: utils::game_elements(game),
+            crit_elements: vec!["Slash", "Strike", "Pierce"],
+            downed_hit: "stays down without granting another One More"
+        },
+        PersonaTitle::P4G => Rules {
+            elements: utils::game_elements(game),
+            crit_elements: vec!["Phys"],
+            downed_hit: "becomes Dizzy and loses its next turn, no extra One More"
+        }
+    }
+}
+
+// "crying table x3", "crying table*3" or "crying table:answer x3"
+pub fn parse_enemy(target: &str) -> (String, Option<String>, usize) {
+    let target = target.trim();
+    let mut count = 1;
+    let mut rest = target;
+
+    if let Some(idx) = target.rfind(['x', 'X', '*']) {
+        let (head, tail) = target.split_at(idx);
+        let digits = &tail[1..];
+        let separated = tail.starts_with('*') || head.ends_with(' ');
+        if separated && !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+            count = digits.parse().unwrap_or(1);
+            rest = head.trim_end();
+        }
+    }
+
+    match rest.split_once(':') {
+        Some((name, variant)) => (name.trim().to_string(), Some(variant.trim().to_string()), count),
+        None => (rest.to_string(), None, count)
+    }
+}
+
+fn affinity_score(affinity: &str) -> isize {
+    match affinity {
+        "Weak" => 3,
+        "Strong" => 0,
+        "Null" => -1,
+        "Repel" | "Drain" => -4,
+        _ => 1
+    }
+}
+
+pub fn plan(enemies: &[Enemy], rules: &Rules) -> Plan {
+    let mut scores: Vec<ElementScore> = rules.elements.iter().map(|element| {
+        let mut score = ElementScore {
+            element: element.to_string(),
+            score: 0,
+            knockdowns: 0,
+            punishers: vec![]
+        };
+
+        for enemy in enemies {
+            let affinity = enemy.info.affinity(element);
+            score.score += affinity_score(&affinity) * enemy.count as isize;
+
+            match affinity.as_str() {
+                "Weak" => score.knockdowns += enemy.count,
+                "Repel" | "Drain" => score.punishers.push(format!("{} ({})", enemy.name, affinity)),
+                _ => {}
+            }
+        }
+
+        score
+    }).collect();
+
+    // anything that gets repelled or drained sinks to the bottom no matter how many it downs
+    scores.sort_by(|a, b| {
+        a.punishers.is_empty().cmp(&b.punishers.is_empty()).reverse()
+            .then(b.knockdowns.cmp(&a.knockdowns))
+            .then(b.score.cmp(&a.score))
+    });
+
+    // greedily pick the element that downs the most remaining enemies, preferring ones
+    // nothing on the field punishes
+    let mut remaining: Vec<&Enemy> = enemies.iter().collect();
+    let mut all_out = vec![];
+    loop {
+        let best = scores.iter()
+            .map(|s| {
+                let downed: Vec<&Enemy> = remaining.iter()
+                    .filter(|e| e.info.affinity(&s.element) == "Weak")
+                    .cloned()
+                    .collect();
+                (s, downed)
+            })
+            .filter(|(_, downed)| !downed.is_empty())
+            .max_by(|(a, a_downed), (b, b_downed)| {
+                let a_count: usize = a_downed.iter().map(|e| e.count).sum();
+                let b_count: usize = b_downed.iter().map(|e| e.count).sum();
+                a_count.cmp(&b_count).then(b.punishers.len().cmp(&a.punishers.len()))
+            });
+
+        match best {
+            Some((score, downed)) => {
+                // by identity, two variants of the same shadow are separate enemies
+                remaining.retain(|e| !downed.iter().any(|d| std::ptr::eq(*d, *e)));
+                all_out.push(Step {
+                    element: score.element.clone(),
+                    targets: downed.iter().map(|e| e.name.clone()).collect()
+                });
+            },
+            None => break
+        }
+    }
+
+    Plan {
+        scores,
+        all_out,
+        unexploitable: remaining.iter().map(|e| e.name.clone()).collect()
+    }
+}
+
+pub fn print_plan(plan: &Plan, rules: &Rules) {
+    println!("{}", "Elements, best first".bold());
+    for score in &plan.scores {
+        let line = format!(
+            "{:8} score {:>3}  knockdowns {}",
+            score.element, score.score, score.knockdowns
+        );
+
+        if !score.punishers.is_empty() {
+            println!("{}  {} {}", line.purple(), "AVOID:".purple().bold(), score.punishers.join(", "));
+        } else if score.knockdowns > 0 {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    println!();
+    println!("{}", "All-out attack".bold());
+    for (idx, step) in plan.all_out.iter().enumerate() {
+        println!("{}. {} -> {}", idx + 1, step.element, step.targets.join(", "));
+    }
+
+    if plan.unexploitable.is_empty() {
+        println!("Every enemy can be knocked down, go for the All-out Attack");
+    } else {
+        println!(
+            "No weakness to exploit on: {}. Only critical {} hits will knock these down",
+            plan.unexploitable.join(", "),
+            rules.crit_elements.join("/")
+        );
+    }
+    println!("Note: a downed enemy hit in its weakness again {}", rules.downed_hit);
+}
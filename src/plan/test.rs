@@ -0,0 +1,89 @@
+use super::*;
+use std::collections::HashMap;
+use crate::wikia::Shadow;
+
+fn reference_enemy(name: &str, variant: &str, count: usize) -> Enemy {
+    let dump: Vec<Shadow> = serde_json::from_str(include_str!("../../shadows.json")).unwrap();
+    let shadow = dump.into_iter().find(|s| s.name == name).unwrap();
+    let info = shadow.info.into_iter().find(|i| i.variant == variant).unwrap();
+
+    Enemy {
+        name: name.to_string(),
+        count,
+        info
+    }
+}
+
+#[test]
+fn parse_enemy_counts() {
+    assert_eq!(parse_enemy("crying table"), ("crying table".to_string(), None, 1));
+    assert_eq!(parse_enemy("crying table x3"), ("crying table".to_string(), None, 3));
+    assert_eq!(parse_enemy("crying table*2"), ("crying table".to_string(), None, 2));
+    assert_eq!(
+        parse_enemy("crying table:answer x3"),
+        ("crying table".to_string(), Some("answer".to_string()), 3)
+    );
+    // trailing x that is part of the name
+    assert_eq!(parse_enemy("hierophant x"), ("hierophant x".to_string(), None, 1));
+}
+
+#[test]
+fn plan_ranks_shared_weakness_first() {
+    let game = utils::determine_game("3");
+    let rules = rules(&game);
+    let enemies = vec![
+        reference_enemy("Wondrous Magus", "The Journey", 1),
+        reference_enemy("Cowardly Maya", "The Journey", 2),
+    ];
+
+    let plan = plan(&enemies, &rules);
+    let best = plan.scores.first().unwrap();
+    assert_eq!(best.element, "Fire");
+    assert_eq!(best.knockdowns, 3);
+
+    let ice = plan.scores.iter().find(|s| s.element == "Ice").unwrap();
+    assert_eq!(ice.punishers, vec!["Wondrous Magus (Repel)".to_string()]);
+
+    assert_eq!(plan.all_out.len(), 1);
+    assert!(plan.unexploitable.is_empty());
+}
+
+#[test]
+fn plan_reports_unexploitable() {
+    let game = utils::determine_game("3");
+    let rules = rules(&game);
+    let enemies = vec![
+        reference_enemy("Cowardly Maya", "The Journey", 1),
+        reference_enemy("Crying Table", "Sub-Boss", 1),
+        reference_enemy("Sleeping Table", "The Journey", 1),
+    ];
+
+    let plan = plan(&enemies, &rules);
+    assert_eq!(plan.all_out.len(), 2);
+    assert!(plan.all_out.iter().any(|s| s.targets.contains(&"Crying Table".to_string())));
+    assert_eq!(plan.unexploitable, vec!["Sleeping Table".to_string()]);
+}
+
+#[test]
+fn rules_differ_per_game() {
+    let p3 = rules(&utils::determine_game("3"));
+    let p4 = rules(&utils::determine_game("4"));
+
+    assert!(p3.elements.contains(&"Slash"));
+    assert!(p4.elements.contains(&"Phys"));
+    assert_ne!(p3.downed_hit, p4.downed_hit);
+}
+
+#[test]
+fn plan_keeps_variants_of_one_shadow_apart() {
+    let game = utils::determine_game("3");
+    let rules = rules(&game);
+    let downed = reference_enemy("Crying Table", "The Journey", 1);
+    let mut standing = downed.clone();
+    standing.info.resistances = HashMap::new();
+    standing.info.resistances.insert("Neutral".to_string(), rules.elements.iter().map(|e| e.to_string()).collect());
+
+    let plan = plan(&[downed, standing], &rules);
+    assert_eq!(plan.all_out.len(), 1);
+    assert_eq!(plan.unexploitable, vec!["Crying Table".to_string()]);
+}
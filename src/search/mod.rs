@@ -0,0 +1,223 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use serde::Serialize;
+use crate::errors::QueryError;
+use crate::utils;
+use crate::wikia::{Kind, Shadow, ShadowInfo};
+
+// A predicate over a single ShadowInfo, i.e. one variant of a shadow
+// e.g. "weak:elec and not (repel:fire or drain:fire)"
+#[derive(Debug, PartialEq)]
+pub enum Query {
+    Affinity { affinity: String, element: String },
+    // part of the shadow's english, japanese or romanized name
+    Name(String),
+    Kind(Kind),
+    Not(Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>)
+}
+
+#[derive(Serialize, Debug)]
+pub struct SearchMatch {
+    pub name: String,
+    pub variants: Vec<String>
+}
+
+impl Query {
+    pub fn affinity(affinity: &str, element: &str) -> anyhow::Result<Query> {
+        let normalized = match utils::normalize_affinity(affinity) {
+            Some(a) => a,
+            None => return Err(QueryError {
+                query: format!("{}:{}", affinity, element),
+                reason: format!("unknown affinity '{}'", affinity)
+            }.into())
+        };
+
+        Ok(Query::Affinity {
+            affinity: normalized,
+            element: utils::normalize_element(element)
+        })
+    }
+
+    pub fn matches(&self, shadow: &Shadow, info: &ShadowInfo) -> bool {
+        match self {
+            Query::Affinity { affinity, element } => info.resistances
+                .get(affinity)
+                .is_some_and(|elements| elements.iter().any(|e| e.eq_ignore_ascii_case(element))),
+            Query::Name(name) => {
+                let names = match &shadow.infobox {
+                    Some(i) => vec![Some(&shadow.name), i.japanese_name.as_ref(), i.romaji.as_ref()],
+                    None => vec![Some(&shadow.name)]
+                };
+
+                names.into_iter().flatten().any(|n| n.to_lowercase().contains(name))
+            },
+            Query::Kind(kind) => shadow.kind == Some(*kind),
+            Query::Not(q) => !q.matches(shadow, info),
+            Query::And(a, b) => a.matches(shadow, info) && b.matches(shadow, info),
+            Query::Or(a, b) => a.matches(shadow, info) || b.matches(shadow, info)
+        }
+    }
+
+    // folds the given queries into a single AND, returning None when there are none
+    pub fn all(queries: Vec<Query>) -> Option<Query> {
+        queries.into_iter().fold(None, |acc, q| match acc {
+            Some(a) => Some(Query::And(Box::new(a), Box::new(q))),
+            None => Some(q)
+        })
+    }
+}
+
+// Grammar, lowest precedence first:
+//   expr   := term (("or" | "||") term)*
+//   term   := factor (("and" | "&&") factor)*
+//   factor := ("not" | "!") factor | "(" expr ")" | affinity ":" element | "name:" text | "kind:" kind
+// text containing spaces can be quoted, e.g. name:"naku tēburu"
+pub fn parse(query: &str) -> anyhow::Result<Query> {
+    let tokens = tokenize(query);
+    let mut parser = Parser { query, tokens, pos: 0 };
+
+    let parsed = parser.expr()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(parser.error(format!("unexpected '{}'", token)));
+    }
+
+    Ok(parsed)
+}
+
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            _ if quoted => current.push(c),
+            '(' | ')' | '!' => {
+                if !current.is_empty() {
+                    tokens.push(current.clone());
+                    current.clear();
+                }
+                tokens.push(c.to_string());
+            },
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(current.clone());
+                    current.clear();
+                }
+            },
+            _ => current.push(c)
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<String>,
+    pos: usize
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<String> {
+        self.tokens.get(self.pos).map(|t| t.to_lowercase())
+    }
+
+    fn error(&self, reason: String) -> anyhow::Error {
+        QueryError {
+            query: self.query.to_string(),
+            reason
+        }.into()
+    }
+
+    fn expr(&mut self) -> anyhow::Result<Query> {
+        let mut left = self.term()?;
+
+        while let Some("or") | Some("||") = self.peek().as_deref() {
+            self.pos += 1;
+            let right = self.term()?;
+            left = Query::Or(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn term(&mut self) -> anyhow::Result<Query> {
+        let mut left = self.factor()?;
+
+        while let Some("and") | Some("&&") = self.peek().as_deref() {
+            self.pos += 1;
+            let right = self.factor()?;
+            left = Query::And(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> anyhow::Result<Query> {
+        let token = match self.peek() {
+            Some(t) => t,
+            None => return Err(self.error("unexpected end of query".to_string()))
+        };
+        self.pos += 1;
+
+        match token.as_str() {
+            "not" | "!" => Ok(Query::Not(Box::new(self.factor()?))),
+            "(" => {
+                let inner = self.expr()?;
+                match self.peek().as_deref() {
+                    Some(")") => {
+                        self.pos += 1;
+                        Ok(inner)
+                    },
+                    _ => Err(self.error("missing ')'".to_string()))
+                }
+            },
+            _ => match token.split_once(':') {
+                Some(("name", name)) if !name.is_empty() => Ok(Query::Name(name.to_string())),
+                Some(("kind", kind)) => match utils::parse_kind(kind) {
+                    Some(k) => Ok(Query::Kind(k)),
+                    None => Err(self.error(format!("unknown kind '{}'", kind)))
+                },
+                Some((affinity, element)) if !element.is_empty() => Query::affinity(affinity, element),
+                _ => Err(self.error(format!("expected affinity:element, got '{}'", token)))
+            }
+        }
+    }
+}
+
+pub fn search(shadows: &[Shadow], query: &Query) -> Vec<SearchMatch> {
+    shadows.iter().filter_map(|shadow| {
+        let variants: Vec<String> = shadow.info.iter()
+            .filter(|info| query.matches(shadow, info))
+            .map(|info| info.variant.clone())
+            .collect();
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(SearchMatch {
+                name: shadow.name.clone(),
+                variants
+            })
+        }
+    }).collect()
+}
+
+pub fn print_matches(matches: &[SearchMatch]) {
+    for m in matches {
+        println!("{} {}", m.name, format!("({})", m.variants.join(", ")).dimmed());
+    }
+
+    println!();
+    println!("{} shadow(s) matched", matches.len());
+}
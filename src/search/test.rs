@@ -0,0 +1,88 @@
+use super::*;
+
+fn reference_dump() -> Vec<Shadow> {
+    serde_json::from_str(include_str!("../../shadows.json")).unwrap()
+}
+
+#[test]
+fn parse_single_term() {
+    let query = parse("weak:electricity").unwrap();
+    assert_eq!(query, Query::Affinity {
+        affinity: "Weak".to_string(),
+        element: "Elec".to_string()
+    });
+}
+
+#[test]
+fn parse_precedence() {
+    // and binds tighter than or
+    let query = parse("weak:fire or weak:ice and not null:dark").unwrap();
+    match query {
+        Query::Or(left, right) => {
+            assert_eq!(*left, Query::affinity("weak", "fire").unwrap());
+            match *right {
+                Query::And(_, r) => assert!(matches!(*r, Query::Not(_))),
+                _ => panic!("expected and on the right of or")
+            }
+        },
+        _ => panic!("expected or at the top level")
+    }
+}
+
+#[test]
+fn parse_parens_and_symbols() {
+    let words = parse("weak:elec and not (repel:fire or drain:fire)").unwrap();
+    let symbols = parse("weak:elec && !(repel:fire || drain:fire)").unwrap();
+    assert_eq!(words, symbols);
+}
+
+#[test]
+fn parse_errors() {
+    assert!(parse("").is_err());
+    assert!(parse("weak").is_err());
+    assert!(parse("squishy:fire").is_err());
+    assert!(parse("(weak:fire").is_err());
+    assert!(parse("weak:fire)").is_err());
+    assert!(parse("weak:fire and").is_err());
+}
+
+#[test]
+fn search_weak_elec() {
+    let shadows = reference_dump();
+    let matches = search(&shadows, &parse("weak:elec").unwrap());
+
+    let cowardly = matches.iter().find(|m| m.name == "Cowardly Maya").unwrap();
+    assert_eq!(cowardly.variants, vec!["The Journey".to_string()]);
+    assert!(matches.iter().any(|m| m.name == "Intrepid Knight"));
+    assert!(matches.iter().all(|m| !m.variants.is_empty()));
+}
+
+#[test]
+fn search_not_excludes() {
+    let shadows = reference_dump();
+    let weak = search(&shadows, &parse("weak:fire").unwrap());
+    let weak_not_ice = search(&shadows, &parse("weak:fire and not weak:ice").unwrap());
+
+    assert!(weak_not_ice.len() <= weak.len());
+    // Cowardly Maya's Answer variant is weak to fire and drains ice
+    let cowardly = weak_not_ice.iter().find(|m| m.name == "Cowardly Maya").unwrap();
+    assert!(cowardly.variants.contains(&"The Answer".to_string()));
+}
+
+#[test]
+fn search_by_japanese_name() {
+    let mut shadows = reference_dump();
+    let table = shadows.iter_mut().find(|s| s.name == "Crying Table").unwrap();
+    table.infobox = Some(crate::wikia::Infobox {
+        japanese_name: Some("泣くテーブル".to_string()),
+        romaji: Some("Naku Tēburu".to_string()),
+        ..Default::default()
+    });
+
+    let matches = search(&shadows, &parse("name:泣く").unwrap());
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].name, "Crying Table");
+
+    let matches = search(&shadows, &parse("name:\"naku tē\" and weak:ice").unwrap());
+    assert_eq!(matches.len(), 1);
+}
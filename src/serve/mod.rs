@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod test;
+
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Value};
+use crate::errors::{NoShadowError, NoVariantError, QueryError};
+use crate::search::{self, Query};
+use crate::source::Source;
+use crate::utils::{self, GAMES};
+use crate::wikia::Game;
+
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value
+}
+
+fn error(status: u16, message: &str) -> Response {
+    Response {
+        status,
+        body: json!({ "error": message })
+    }
+}
+
+// lookups that didn't find anything are the client's problem, everything else is ours or the wiki's
+fn from_result(result: anyhow::Result<Value>) -> Response {
+    match result {
+        Ok(body) => Response { status: 200, body },
+        Err(e) if e.is::<NoShadowError>() || e.is::<NoVariantError>() => error(404, &e.to_string()),
+        Err(e) if e.is::<QueryError>() => error(400, &e.to_string()),
+        Err(e) => error(502, &e.to_string())
+    }
+}
+
+fn decode(text: &str) -> String {
+    percent_decode_str(&text.replace('+', " ")).decode_utf8_lossy().to_string()
+}
+
+// "weak=fire&weak=ice,elec&q=..." -> [("weak", "fire"), ("weak", "ice,elec"), ("q", "...")]
+fn query_params(query: &str) -> Vec<(String, String)> {
+    query.split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| match p.split_once('=') {
+            Some((k, v)) => (decode(k), decode(v)),
+            None => (decode(p), String::new())
+        })
+        .collect()
+}
+
+fn game(id: &str) -> Option<Game> {
+    let id = id.to_lowercase();
+    if GAMES.contains(&id.as_str()) {
+        Some(utils::determine_game(&id))
+    } else {
+        None
+    }
+}
+
+fn games() -> Value {
+    Value::Array(GAMES.iter().map(|id| {
+        let game = utils::determine_game(id);
+        json!({
+            "id": id,
+            "name": game.entry_text,
+            "variant": game.tab_names.first()
+        })
+    }).collect())
+}
+
+// /search?game=3&weak=fire&immune=ice&name=maya&q=drain:elec
+fn search(source: &dyn Source, params: &[(String, String)]) -> anyhow::Result<Value> {
+    let mut game_id = "3".to_string();
+    let mut queries = vec![];
+
+    for (key, value) in params {
+        match key.as_str() {
+            "game" => game_id = value.clone(),
+            "q" => queries.push(search::parse(value)?),
+            "weak" | "immune" | "strong" | "repel" | "drain" | "null" => {
+                for element in value.split(',').filter(|e| !e.trim().is_empty()) {
+                    queries.push(Query::affinity(key, element.trim())?);
+                }
+            },
+            "name" => queries.push(Query::Name(value.to_lowercase())),
+            _ => {}
+        }
+    }
+
+    let game = match game(&game_id) {
+        Some(g) => g,
+        None => return Err(QueryError {
+            query: game_id,
+            reason: format!("unknown game, expected one of: {}", GAMES.join(", "))
+        }.into())
+    };
+    let query = match Query::all(queries) {
+        Some(q) => q,
+        None => return Err(QueryError {
+            query: String::new(),
+            reason: "nothing to search for, use weak, immune, name or q".to_string()
+        }.into())
+    };
+
+    Ok(serde_json::to_value(search::search(&source.shadows(&game)?, &query))?)
+}
+
+// Everything besides the actual http, so it can be tested without a socket
+pub fn handle(source: &dyn Source, url: &str) -> Response {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<String> = path.split('/').filter(|s| !s.is_empty()).map(decode).collect();
+    let segments: Vec<&str> = segments.iter().map(|s| s.as_str()).collect();
+
+    match segments.as_slice() {
+        ["games"] => Response { status: 200, body: games() },
+        ["games", id, rest @ ..] => {
+            let game = match game(id) {
+                Some(g) => g,
+                None => return error(404, &format!("Unknown game: {}", id))
+            };
+
+            match rest {
+                ["shadows"] => from_result(source.names(&game).map(|names| json!(names))),
+                ["shadows", name] => from_result(source.shadow(name, &game).and_then(|s| Ok(serde_json::to_value(s)?))),
+                _ => error(404, "Not found")
+            }
+        },
+        ["search"] => from_result(search(source, &query_params(query))),
+        _ => error(404, "Not found")
+    }
+}
+
+pub fn serve(address: &str, source: &dyn Source) -> anyhow::Result<()> {
+    let server = tiny_http::Server::http(address).map_err(|e| anyhow::anyhow!("Could not listen on {}: {}", address, e))?;
+    eprintln!("Listening on http://{}", address);
+
+    let content_type = tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap();
+    for request in server.incoming_requests() {
+        let response = if *request.method() == tiny_http::Method::Get {
+            handle(source, request.url())
+        } else {
+            error(405, "Only GET is supported")
+        };
+
+        let reply = tiny_http::Response::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(content_type.clone());
+        if let Err(e) = request.respond(reply) {
+            eprintln!("{}", e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use super::*;
+use crate::source::Dump;
+
+fn reference_source() -> Dump {
+    Dump {
+        shadows: serde_json::from_str(include_str!("../../shadows.json")).unwrap()
+    }
+}
+
+#[test]
+fn games_list() {
+    let response = handle(&reference_source(), "/games");
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body[0]["id"], "3");
+    assert_eq!(response.body[2]["name"], "Persona 4");
+}
+
+#[test]
+fn shadows_and_shadow() {
+    let source = reference_source();
+
+    let names = handle(&source, "/games/3/shadows");
+    assert_eq!(names.status, 200);
+    assert!(names.body.as_array().unwrap().iter().any(|n| n == "Crying Table"));
+
+    let shadow = handle(&source, "/games/3/shadows/crying%20table");
+    assert_eq!(shadow.status, 200);
+    assert_eq!(shadow.body["name"], "Crying Table");
+    assert_eq!(shadow.body["info"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn not_found() {
+    let source = reference_source();
+
+    assert_eq!(handle(&source, "/games/3/shadows/nobody").status, 404);
+    assert_eq!(handle(&source, "/games/5/shadows").status, 404);
+    assert_eq!(handle(&source, "/nothing").status, 404);
+}
+
+#[test]
+fn search_weak() {
+    let source = reference_source();
+
+    let response = handle(&source, "/search?weak=elec");
+    assert_eq!(response.status, 200);
+    let cowardly = response.body.as_array().unwrap().iter().find(|m| m["name"] == "Cowardly Maya").unwrap();
+    assert_eq!(cowardly["variants"], json!(["The Journey"]));
+
+    let both = handle(&source, "/search?game=3&q=weak%3Afire+and+not+weak%3Aice");
+    assert_eq!(both.status, 200);
+    assert!(both.body.as_array().unwrap().len() <= handle(&source, "/search?weak=fire").body.as_array().unwrap().len());
+}
+
+#[test]
+fn search_bad_query() {
+    let source = reference_source();
+
+    assert_eq!(handle(&source, "/search").status, 400);
+    assert_eq!(handle(&source, "/search?q=squishy:fire").status, 400);
+    assert_eq!(handle(&source, "/search?q=weak:fire+and").status, 400);
+    assert_eq!(handle(&source, "/search?game=7&weak=fire").status, 400);
+}
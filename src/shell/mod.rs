@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::search::{self, Query};
+use crate::source::Source;
+use crate::wikia::Game;
+use crate::{cache, compare, utils};
+
+const COMMANDS: &[&str] = &["game", "look", "compare", "weak", "search", "list", "help", "quit"];
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    // no argument shows the current game
+    Game(Option<String>),
+    Look(String),
+    Compare(Vec<String>),
+    Weak(Vec<String>),
+    Search(String),
+    List,
+    Help,
+    Quit
+}
+
+pub fn parse_command(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+
+    let needs = |what: &str| Err(format!("{} needs {}", command, what));
+    match command.to_lowercase().as_str() {
+        "game" | "g" if rest.is_empty() => Ok(Command::Game(None)),
+        // determine_game falls back to Persona 3 for anything it doesn't know
+        "game" | "g" => if utils::GAME_ALIASES.contains(&rest.to_lowercase().as_str()) {
+            Ok(Command::Game(Some(rest.to_string())))
+        } else {
+            Err(format!("Unknown game: {}, expected one of: {}", rest, utils::GAMES.join(", ")))
+        },
+        "look" | "l" => if rest.is_empty() { needs("a shadow name") } else { Ok(Command::Look(rest.to_string())) },
+        "compare" | "c" => {
+            let targets: Vec<String> = rest.split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+            if targets.is_empty() { needs("shadow names separated by commas") } else { Ok(Command::Compare(targets)) }
+        },
+        "weak" | "w" => {
+            let elements: Vec<String> = rest.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|e| !e.is_empty())
+                .map(|e| e.to_string())
+                .collect();
+            if elements.is_empty() { needs("an element") } else { Ok(Command::Weak(elements)) }
+        },
+        "search" | "s" => if rest.is_empty() { needs("a query") } else { Ok(Command::Search(rest.to_string())) },
+        "list" | "ls" => Ok(Command::List),
+        "help" | "?" => Ok(Command::Help),
+        "quit" | "exit" | "q" => Ok(Command::Quit),
+        other => Err(format!("Unknown command: {}, try help", other))
+    }
+}
+
+// Replacements for the word being typed, and where it starts. Shadow names complete after
+// look and after every comma of compare, since they have spaces in them
+pub fn completions(line: &str, names: &[String], game: &Game) -> (usize, Vec<String>) {
+    let matching = |candidates: Vec<String>, typed: &str| -> Vec<String> {
+        candidates.into_iter()
+            .filter(|c| c.to_lowercase().starts_with(&typed.to_lowercase()))
+            .collect()
+    };
+
+    let (command, _) = match line.split_once(' ') {
+        Some(split) => split,
+        None => return (0, matching(COMMANDS.iter().map(|c| c.to_string()).collect(), line))
+    };
+    let command_len = command.len();
+    let command = command.to_lowercase();
+
+    let start = match command.as_str() {
+        "compare" | "c" => line.rfind(',').map(|i| i + 1).unwrap_or(command_len + 1),
+        "weak" | "w" => line.rfind([' ', ',']).map(|i| i + 1).unwrap_or(line.len()),
+        _ => command_len + 1
+    };
+    // leave the spaces after a comma or the command alone
+    let start = start + line[start..].len() - line[start..].trim_start().len();
+    let typed = &line[start..];
+
+    let candidates = match command.as_str() {
+        "look" | "l" | "compare" | "c" => names.to_vec(),
+        "game" | "g" => utils::GAMES.iter().map(|g| g.to_string()).collect(),
+        "weak" | "w" => utils::game_elements(game).iter().map(|e| e.to_lowercase()).collect(),
+        _ => vec![]
+    };
+
+    (start, matching(candidates, typed))
+}
+
+struct ShellHelper {
+    names: Rc<RefCell<Vec<String>>>,
+    game: Rc<RefCell<Game>>
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, candidates) = completions(&line[..pos], &self.names.borrow(), &self.game.borrow());
+        Ok((start, candidates.into_iter().map(|c| Pair { display: c.clone(), replacement: c }).collect()))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+fn print_help() {
+    println!("game [3|3a|4]               show or switch the game");
+    println!("look <shadow>               resistances of a shadow");
+    println!("compare <shadow>, <shadow>  side by side, \"name:variant\" picks a variant");
+    println!("weak <element>...           shadows weak to all of the elements");
+    println!("search <query>              e.g. weak:elec and not drain:fire");
+    println!("list                        every shadow in the game");
+    println!("quit");
+}
+
+// the shadow index for completion, a failure only costs completion
+fn load_names(source: &dyn Source, game: &Game) -> Vec<String> {
+    match source.names(game) {
+        Ok(names) => names,
+        Err(e) => {
+            eprintln!("{} could not load shadow names for completion: {}", "warning:".yellow(), e);
+            vec![]
+        }
+    }
+}
+
+fn run(command: Command, source: &dyn Source, game: &Rc<RefCell<Game>>, names: &Rc<RefCell<Vec<String>>>) -> anyhow::Result<()> {
+    match command {
+        Command::Game(None) => println!("{} ({})", game.borrow().entry_text, game.borrow().tab_names.join(", ")),
+        Command::Game(Some(id)) => {
+            *game.borrow_mut() = utils::determine_game(&id);
+            *names.borrow_mut() = load_names(source, &game.borrow());
+            println!("Switched to {}", game.borrow().entry_text);
+        },
+        Command::Look(name) => utils::print_resistances(&source.shadow(&name, &game.borrow())?),
+        Command::Compare(targets) => {
+            let mut shadows = vec![];
+            for target in &targets {
+                let (name, variant) = compare::parse_target(target);
+                let mut shadow = source.shadow(&name, &game.borrow())?;
+                compare::retain_variant(&mut shadow, &variant, &game.borrow())?;
+                shadows.push(shadow);
+            }
+            compare::print_comparison(&compare::compare(&shadows, &utils::game_elements(&game.borrow())));
+        },
+        Command::Weak(elements) => {
+            let queries = elements.iter()
+                .map(|e| Query::affinity("weak", e))
+                .collect::<anyhow::Result<Vec<Query>>>()?;
+            if let Some(query) = Query::all(queries) {
+                search::print_matches(&search::search(&source.shadows(&game.borrow())?, &query));
+            }
+        },
+        Command::Search(query) => {
+            let query = search::parse(&query)?;
+            search::print_matches(&search::search(&source.shadows(&game.borrow())?, &query));
+        },
+        Command::List => {
+            for name in names.borrow().iter() {
+                println!("{}", name);
+            }
+        },
+        Command::Help => print_help(),
+        Command::Quit => {}
+    }
+
+    Ok(())
+}
+
+pub fn shell(source: &dyn Source, game: Game) -> anyhow::Result<()> {
+    let names = Rc::new(RefCell::new(load_names(source, &game)));
+    let game = Rc::new(RefCell::new(game));
+
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(ShellHelper {
+        names: names.clone(),
+        game: game.clone()
+    }));
+    // history lives next to the page cache
+    let history = cache::cache_dir().map(|dir| dir.join("shell_history"));
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    println!("{} shadows loaded for {}, help lists the commands", names.borrow().len(), game.borrow().entry_text);
+    loop {
+        let prompt = format!("liz ({})> ", game.borrow().entry_text);
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into())
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line.as_str());
+
+        match parse_command(&line) {
+            Ok(Command::Quit) => break,
+            Ok(command) => if let Err(e) = run(command, source, &game, &names) {
+                eprintln!("{}", e);
+            },
+            Err(e) => eprintln!("{}", e)
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = editor.save_history(path);
+    }
+
+    Ok(())
+}
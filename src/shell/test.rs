@@ -0,0 +1,52 @@
+use super::*;
+
+fn names() -> Vec<String> {
+    vec!["Crying Table".to_string(), "Cowardly Maya".to_string(), "Laughing Table".to_string()]
+}
+
+#[test]
+fn parse_commands() {
+    assert_eq!(parse_command("game 4g"), Ok(Command::Game(Some("4g".to_string()))));
+    assert_eq!(parse_command("game"), Ok(Command::Game(None)));
+    assert_eq!(parse_command("look crying table"), Ok(Command::Look("crying table".to_string())));
+    assert_eq!(parse_command("compare crying table:answer, cowardly maya"), Ok(Command::Compare(vec![
+        "crying table:answer".to_string(),
+        "cowardly maya".to_string()
+    ])));
+    assert_eq!(parse_command("weak fire, ice"), Ok(Command::Weak(vec!["fire".to_string(), "ice".to_string()])));
+    assert_eq!(parse_command("  q "), Ok(Command::Quit));
+}
+
+#[test]
+fn parse_command_errors() {
+    assert!(parse_command("look").is_err());
+    assert!(parse_command("weak").is_err());
+    assert!(parse_command("compare , ,").is_err());
+    assert!(parse_command("dance").is_err());
+    assert!(parse_command("game 5").is_err());
+    assert!(parse_command("game p4").is_err());
+    assert_eq!(parse_command("game 4G"), Ok(Command::Game(Some("4G".to_string()))));
+}
+
+#[test]
+fn complete_commands_and_names() {
+    let game = utils::determine_game("3");
+
+    assert_eq!(completions("co", &names(), &game), (0, vec!["compare".to_string()]));
+    assert_eq!(completions("look cr", &names(), &game), (5, vec!["Crying Table".to_string()]));
+    assert_eq!(completions("look crying t", &names(), &game), (5, vec!["Crying Table".to_string()]));
+    assert_eq!(completions("LOOK cr", &names(), &game), (5, vec!["Crying Table".to_string()]));
+    assert_eq!(
+        completions("compare crying table, c", &names(), &game),
+        (22, vec!["Crying Table".to_string(), "Cowardly Maya".to_string()])
+    );
+}
+
+#[test]
+fn complete_games_and_elements() {
+    let game = utils::determine_game("4");
+
+    assert_eq!(completions("game ", &names(), &game), (5, vec!["3".to_string(), "3a".to_string(), "4".to_string()]));
+    assert_eq!(completions("weak fire p", &names(), &game), (10, vec!["phys".to_string()]));
+    assert_eq!(completions("weak fire,i", &names(), &game), (10, vec!["ice".to_string()]));
+}
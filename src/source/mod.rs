@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::utils;
+use crate::wikia::{self, Backend, Game, Shadow, Skipped};
+
+// Where long running commands (serve, shell) get their shadows from
+pub trait Source {
+    fn names(&self, game: &Game) -> anyhow::Result<Vec<String>>;
+    fn shadow(&self, name: &str, game: &Game) -> anyhow::Result<Shadow>;
+    fn shadows(&self, game: &Game) -> anyhow::Result<Vec<Shadow>>;
+
+    // shadows() for callers that own the terminal: the skipped shadows come back instead of
+    // being printed, and progress gets (done, total) while pages are fetched
+    fn shadows_with_progress(
+        &self,
+        game: &Game,
+        _progress: &mut dyn FnMut(usize, usize)
+    ) -> anyhow::Result<(Vec<Shadow>, Vec<Skipped>)> {
+        Ok((self.shadows(game)?, vec![]))
+    }
+}
+
+// The wiki, through the page cache. Anything fetched is also kept in memory for as long as the
+// process runs, whole games once something needed all of them
+pub struct Wiki {
+    games: Mutex<HashMap<String, Vec<Shadow>>>,
+    shadows: Mutex<HashMap<String, Shadow>>
+}
+
+impl Wiki {
+    pub fn new() -> Wiki {
+        Wiki {
+            games: Mutex::new(HashMap::new()),
+            shadows: Mutex::new(HashMap::new())
+        }
+    }
+
+    fn loaded(&self, game: &Game) -> Option<Vec<Shadow>> {
+        self.games.lock().ok()?.get(&game_key(game)).cloned()
+    }
+}
+
+fn game_key(game: &Game) -> String {
+    format!("{:?}", game.entry)
+}
+
+impl Source for Wiki {
+    fn names(&self, game: &Game) -> anyhow::Result<Vec<String>> {
+        wikia::shadow_names(game)
+    }
+
+    fn shadow(&self, name: &str, game: &Game) -> anyhow::Result<Shadow> {
+        if let Some(shadows) = self.loaded(game) {
+            return utils::find_shadow(&shadows, name, game);
+        }
+
+        let key = format!("{}/{}", game_key(game), name.trim().to_lowercase());
+        if let Some(shadow) = self.shadows.lock().ok().and_then(|s| s.get(&key).cloned()) {
+            return Ok(shadow);
+        }
+
+        let shadow = wikia::get_shadow(name, game)?;
+        if let Ok(mut shadows) = self.shadows.lock() {
+            shadows.insert(key, shadow.clone());
+        }
+        Ok(shadow)
+    }
+
+    fn shadows(&self, game: &Game) -> anyhow::Result<Vec<Shadow>> {
+        let (shadows, skipped) = self.shadows_with_progress(game, &mut |_, _| {})?;
+        wikia::print_skipped(&skipped);
+
+        Ok(shadows)
+    }
+
+    fn shadows_with_progress(
+        &self,
+        game: &Game,
+        progress: &mut dyn FnMut(usize, usize)
+    ) -> anyhow::Result<(Vec<Shadow>, Vec<Skipped>)> {
+        if let Some(shadows) = self.loaded(game) {
+            return Ok((shadows, vec![]));
+        }
+
+        let (shadows, skipped) = wikia::arcana_sections_progress(game, Backend::Html, progress)?;
+        if let Ok(mut games) = self.games.lock() {
+            games.insert(game_key(game), shadows.clone());
+        }
+        Ok((shadows, skipped))
+    }
+}
+
+// A dump created with -a, nothing gets fetched
+pub struct Dump {
+    pub shadows: Vec<Shadow>
+}
+
+impl Source for Dump {
+    fn names(&self, game: &Game) -> anyhow::Result<Vec<String>> {
+        Ok(self.shadows(game)?.into_iter().map(|s| s.name).collect())
+    }
+
+    fn shadow(&self, name: &str, game: &Game) -> anyhow::Result<Shadow> {
+        utils::find_shadow(&self.shadows(game)?, name, game)
+    }
+
+    fn shadows(&self, game: &Game) -> anyhow::Result<Vec<Shadow>> {
+        Ok(utils::for_game(self.shadows.clone(), game))
+    }
+}
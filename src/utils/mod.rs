@@ -1,5 +1,14 @@
 use colored::*;
-use crate::wikia::{Game, PersonaTitle, Shadow};
+use inflector::Inflector;
+use std::fs;
+use crate::dump;
+use crate::errors::NoShadowError;
+use crate::wikia::{Game, Kind, PersonaTitle, Shadow, ShadowInfo};
+
+// the ids determine_game knows, besides the aliases
+pub const GAMES: &[&str] = &["3", "3a", "4"];
+// everything determine_game accepts
+pub const GAME_ALIASES: &[&str] = &["3", "3j", "3a", "4", "4g"];
 
 pub fn determine_game(game: &str) -> Game {
     match game.to_lowercase().as_str() {
@@ -30,6 +39,58 @@ pub fn determine_game(game: &str) -> Game {
     }
 }
 
+pub fn game_elements(game: &Game) -> Vec<&'static str> {
+    match game.entry {
+        PersonaTitle::P3J | PersonaTitle::P3A => vec![
+            "Slash", "Strike", "Pierce", "Fire", "Ice", "Elec", "Wind", "Light", "Dark", "Almi"
+        ],
+        PersonaTitle::P4G => vec![
+            "Phys", "Fire", "Ice", "Elec", "Wind", "Light", "Dark", "Almi"
+        ]
+    }
+}
+
+// Reads a dump created with -a, keeping only the info for the given game
+pub fn read_dump(path: &str, game: &Game) -> anyhow::Result<Vec<Shadow>> {
+    let (_, dump) = dump::parse(&fs::read_to_string(path)?)?;
+
+    Ok(for_game(dump, game))
+}
+
+// the shadows in a dump appearing in the game, with only that game's info and locations
+pub fn for_game(dump: Vec<Shadow>, game: &Game) -> Vec<Shadow> {
+    dump.into_iter()
+        .map(|mut s| {
+            s.info.retain(|i| i.game == game.entry_text);
+            s.locations.retain(|l| l.game == game.entry_text);
+            s
+        })
+        .filter(|s| !s.info.is_empty())
+        .collect()
+}
+
+pub fn find_shadow(dump: &[Shadow], name: &str, game: &Game) -> anyhow::Result<Shadow> {
+    match dump.iter().find(|s| s.name.eq_ignore_ascii_case(name.trim())) {
+        Some(s) => Ok(s.clone()),
+        None => Err(NoShadowError {
+            name: name.to_title_case(),
+            game: game.entry_text.clone()
+        }.into())
+    }
+}
+
+// Picks the variant that best represents a shadow in the given game: one named after
+// the game's tabs first, then the game's usual variant, falling back to whatever comes first
+pub fn preferred_info<'a>(shadow: &'a Shadow, game: &Game) -> Option<&'a ShadowInfo> {
+    let named = |name: &String| shadow.info.iter()
+        .find(|i| i.variant.to_lowercase().contains(&name.to_lowercase()));
+
+    game.tab_names.iter()
+        .find_map(named)
+        .or_else(|| game.variant.as_ref().and_then(named))
+        .or_else(|| shadow.info.first())
+}
+
 // Necessary to handle the seemingly random cases when resistance text
 // is surrounded by some html tag, e.g. <span>Weak</span>
 pub fn strip_cell_tags(cell: String) -> String {
@@ -48,12 +109,121 @@ pub fn strip_cell_tags(cell: String) -> String {
     "Neutral".to_string()
 }
 
+// Maps what a user is likely to type onto the affinity names used in resistance tables
+pub fn normalize_affinity(affinity: &str) -> Option<String> {
+    let normalized = match affinity.to_lowercase().as_str() {
+        "weak" => "Weak",
+        "strong" | "resist" => "Strong",
+        "null" | "immune" | "block" => "Null",
+        "repel" | "reflect" => "Repel",
+        "drain" | "absorb" => "Drain",
+        "neutral" | "-" => "Neutral",
+        _ => return None
+    };
+
+    Some(normalized.to_string())
+}
+
+// Same as above, but for element table headers, e.g. "electricity" -> "Elec"
+pub fn normalize_element(element: &str) -> String {
+    match element.to_lowercase().as_str() {
+        "elec" | "electric" | "electricity" => "Elec".to_string(),
+        "almi" | "almighty" => "Almi".to_string(),
+        "phys" | "physical" => "Phys".to_string(),
+        other => other.to_title_case()
+    }
+}
+
+// Levenshtein distance, for putting up with the wiki's typos
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+pub fn parse_kind(kind: &str) -> Option<Kind> {
+    match kind.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+        "boss" | "bosses" => Some(Kind::Boss),
+        "miniboss" | "subboss" | "minibosses" | "subbosses" => Some(Kind::MiniBoss),
+        "normal" | "shadow" => Some(Kind::Normal),
+        _ => None
+    }
+}
+
+// "Persona 3 (FES, Portable), Persona 4 (Golden)"
+fn appearances_text(shadow: &Shadow) -> String {
+    let mut games: Vec<(String, Vec<String>)> = vec![];
+    for appearance in &shadow.appearances {
+        let idx = match games.iter().position(|(g, _)| *g == appearance.game) {
+            Some(idx) => idx,
+            None => {
+                games.push((appearance.game.clone(), vec![]));
+                games.len() - 1
+            }
+        };
+
+        if let Some(v) = &appearance.version {
+            games[idx].1.push(v.clone());
+        }
+    }
+
+    games.iter()
+        .map(|(game, versions)| if versions.is_empty() {
+            game.clone()
+        } else {
+            format!("{} ({})", game, versions.join(", "))
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn print_infobox(shadow: &Shadow) {
+    let infobox = shadow.infobox.clone().unwrap_or_default();
+    let kind = shadow.kind.map(|k| match k {
+        Kind::Boss => "Boss".to_string(),
+        Kind::MiniBoss => "Mini-boss".to_string(),
+        Kind::Normal => "Normal".to_string()
+    });
+
+    let fields = vec![
+        ("Japanese Name", infobox.japanese_name),
+        ("Romaji", infobox.romaji),
+        ("Arcana", if shadow.arcana.is_empty() { None } else { Some(shadow.arcana.join(", ")) }),
+        ("Kind", kind),
+        ("First Appearance", infobox.first_appearance),
+        ("Appearances", if shadow.appearances.is_empty() { None } else { Some(appearances_text(shadow)) }),
+        ("Species", infobox.species),
+        ("Image", infobox.image),
+        ("Page Layout", shadow.layout.clone())
+    ];
+
+    for (label, value) in fields {
+        if let Some(v) = value {
+            println!("{} {}", format!("{}:", label).dimmed(), v);
+        }
+    }
+    println!();
+}
+
 pub fn print_resistances(shadow: &Shadow) {
     println!("{}", shadow.name);
     println!();
 
     for tab in &shadow.info {
         println!("{}", tab.variant);
+        for warning in &tab.warnings {
+            println!("{} {}", "warning:".yellow(), warning);
+        }
 
         for (resistance, kinds) in &tab.resistances {
             match resistance.as_str() {
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod test;
+
+use colored::*;
+use crate::diff::{self, ShadowChange};
+use crate::wikia::Shadow;
+
+#[derive(Debug)]
+pub enum Outcome {
+    Match,
+    Mismatch(ShadowChange),
+    // couldn't be scraped at all
+    Failed(String)
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub results: Vec<(String, Outcome)>
+}
+
+impl Report {
+    pub fn mismatches(&self) -> usize {
+        self.results.iter().filter(|(_, o)| !matches!(o, Outcome::Match)).count()
+    }
+}
+
+// Every nth shadow, so a sample covers the whole dump instead of the first few arcana
+pub fn sample(reference: &[Shadow], size: usize) -> Vec<&Shadow> {
+    if size == 0 || size >= reference.len() {
+        return reference.iter().collect();
+    }
+
+    let step = reference.len() as f64 / size as f64;
+    (0..size).map(|i| &reference[(i as f64 * step) as usize]).collect()
+}
+
+// Scrapes each shadow again with the given function and compares it to the reference
+pub fn verify<F>(shadows: &[&Shadow], scrape: F) -> Report
+where
+    F: Fn(&str) -> anyhow::Result<Shadow>
+{
+    let results = shadows.iter()
+        .map(|reference| {
+            let outcome = match scrape(&reference.name) {
+                Ok(scraped) => match diff::diff_shadow(reference, &scraped) {
+                    Some(change) => Outcome::Mismatch(change),
+                    None => Outcome::Match
+                },
+                Err(e) => Outcome::Failed(e.to_string())
+            };
+
+            (reference.name.clone(), outcome)
+        })
+        .collect();
+
+    Report { results }
+}
+
+pub fn print_report(report: &Report) {
+    for (name, outcome) in &report.results {
+        match outcome {
+            Outcome::Match => println!("{} {}", "ok".green(), name),
+            Outcome::Failed(e) => println!("{} {}: {}", "failed".red(), name, e),
+            Outcome::Mismatch(change) => {
+                println!("{} {}", "mismatch".yellow(), name);
+                for variant in &change.removed_variants {
+                    println!("    missing variant {}", variant);
+                }
+                for variant in &change.added_variants {
+                    println!("    unexpected variant {}", variant);
+                }
+                for variant in &change.changed_variants {
+                    println!("    {}", variant.variant);
+                    for c in &variant.changes {
+                        println!("      {}: expected {}, got {}", c.element, c.old.green(), c.new.red());
+                    }
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{} of {} shadow(s) match the reference", report.results.len() - report.mismatches(), report.results.len());
+}
@@ -0,0 +1,46 @@
+use super::*;
+use crate::utils;
+
+fn reference_dump() -> Vec<Shadow> {
+    serde_json::from_str(include_str!("../../shadows.json")).unwrap()
+}
+
+#[test]
+fn sample_spreads_out() {
+    let shadows = reference_dump();
+
+    let picked = sample(&shadows, 4);
+    assert_eq!(picked.len(), 4);
+    assert_eq!(picked[0].name, shadows[0].name);
+    assert_eq!(picked[2].name, shadows[shadows.len() / 2].name);
+
+    assert_eq!(sample(&shadows, 0).len(), shadows.len());
+    assert_eq!(sample(&shadows, shadows.len() + 1).len(), shadows.len());
+}
+
+#[test]
+fn verify_reports_each_outcome() {
+    let shadows = reference_dump();
+    let game = utils::determine_game("3");
+    let picked: Vec<&Shadow> = ["Crying Table", "Cowardly Maya", "Intrepid Knight"].iter()
+        .map(|name| shadows.iter().find(|s| s.name == *name).unwrap())
+        .collect();
+
+    let report = verify(&picked, |name| {
+        let mut scraped = utils::find_shadow(&shadows, name, &game)?;
+        match name {
+            "Cowardly Maya" => scraped.info.pop(),
+            "Intrepid Knight" => anyhow::bail!("page layout not recognized"),
+            _ => None
+        };
+        Ok(scraped)
+    });
+
+    assert_eq!(report.mismatches(), 2);
+    assert!(matches!(report.results[0].1, Outcome::Match));
+    match &report.results[1].1 {
+        Outcome::Mismatch(change) => assert_eq!(change.removed_variants.len(), 1),
+        other => panic!("expected a mismatch, got {:?}", other)
+    }
+    assert!(matches!(report.results[2].1, Outcome::Failed(_)));
+}
@@ -0,0 +1,142 @@
+// What --debug-parse prints: every layout's selector and how often it hit, what was picked,
+// and enough of the html to tell what the page actually looks like
+use colored::*;
+use scraper::{Html, Selector};
+use super::{game_section_with, game_table, layouts, Game};
+
+const SNIPPET_LENGTH: usize = 600;
+
+#[derive(Debug)]
+pub struct Attempt {
+    pub layout: &'static str,
+    pub selector: String,
+    // None when the selector didn't even parse
+    pub hits: Option<usize>
+}
+
+#[derive(Debug)]
+pub struct TableReport {
+    pub variant: String,
+    pub headers: usize,
+    pub cells: usize
+}
+
+#[derive(Debug)]
+pub struct ParseReport {
+    pub shadow: String,
+    pub game: String,
+    pub attempts: Vec<Attempt>,
+    pub layout: Option<&'static str>,
+    pub error: Option<String>,
+    pub tables: Vec<TableReport>,
+    pub snippet: String
+}
+
+pub fn diagnose(page: &Html, game: &Game, shadow_name: &str) -> ParseReport {
+    let chain = layouts::default_layouts();
+
+    let attempts = chain.iter().map(|layout| {
+        let selector = layout.selector(game);
+        let hits = Selector::parse(selector.as_str()).ok().map(|s| page.select(&s).count());
+
+        Attempt {
+            layout: layout.name(),
+            selector,
+            hits
+        }
+    }).collect();
+
+    let mut report = ParseReport {
+        shadow: shadow_name.to_string(),
+        game: game.entry_text.clone(),
+        attempts,
+        layout: None,
+        error: None,
+        tables: vec![],
+        snippet: snippet(&stats_html(page))
+    };
+
+    let section = match game_section_with(page, game, shadow_name.to_string(), &chain) {
+        Ok((section, layout)) => {
+            report.layout = Some(layout);
+            section
+        },
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return report;
+        }
+    };
+    report.snippet = snippet(&section.root_element().html());
+
+    let header_selector = Selector::parse("tbody > tr:nth-child(1) > th").unwrap();
+    let cell_selector = Selector::parse("tbody > tr:nth-child(2) > td").unwrap();
+    match game_table(&section) {
+        Ok(tables) => {
+            report.tables = tables.iter().map(|(table, variant)| TableReport {
+                variant: variant.clone(),
+                headers: table.select(&header_selector).count(),
+                cells: table.select(&cell_selector).count()
+            }).collect();
+        },
+        Err(e) => report.error = Some(e.to_string())
+    }
+
+    report
+}
+
+fn stats_html(page: &Html) -> String {
+    let stats_selector = Selector::parse("#Stats-collapsible-section").unwrap();
+    match page.select(&stats_selector).next() {
+        Some(stats) => stats.html(),
+        None => page.root_element().html()
+    }
+}
+
+// whitespace collapsed and cut short, it's going into a bug report
+fn snippet(html: &str) -> String {
+    let collapsed = html.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    match collapsed.char_indices().nth(SNIPPET_LENGTH) {
+        Some((idx, _)) => format!("{}...", &collapsed[..idx]),
+        None => collapsed
+    }
+}
+
+pub fn print_report(report: &ParseReport) {
+    eprintln!("{} {} ({})", "parse report for".bold(), report.shadow, report.game);
+
+    eprintln!("layouts tried, in order:");
+    for attempt in &report.attempts {
+        let hits = match attempt.hits {
+            Some(h) => h.to_string(),
+            None => "invalid selector".to_string()
+        };
+        let marker = if report.layout == Some(attempt.layout) { "*" } else { " " };
+        eprintln!("  {} {:24} {:>3}  {}", marker, attempt.layout, hits, attempt.selector.dimmed());
+    }
+
+    match report.layout {
+        Some(layout) => eprintln!("matched layout: {}", layout.green()),
+        None => eprintln!("matched layout: {}", "none".red())
+    }
+
+    if !report.tables.is_empty() {
+        eprintln!("tabs:");
+    }
+    for table in &report.tables {
+        let counts = format!("{} headers, {} cells", table.headers, table.cells);
+        if table.headers == 0 || table.headers != table.cells {
+            eprintln!("  {} ({})", table.variant, counts.red());
+        } else {
+            eprintln!("  {} ({})", table.variant, counts);
+        }
+    }
+
+    if let Some(error) = &report.error {
+        eprintln!("error: {}", error.red());
+    }
+
+    eprintln!("html considered:");
+    eprintln!("  {}", report.snippet.dimmed());
+    eprintln!();
+}
@@ -0,0 +1,162 @@
+// The ways a game's stats section has been laid out on shadow pages. Each layout is tried
+// in order until one's selector hits, so more specific layouts need to come first
+use scraper::{Html, Selector};
+use super::{Game, PersonaTitle, P3_SELECTOR, P4_SELECTOR};
+
+pub trait Layout {
+    fn name(&self) -> &'static str;
+
+    // selector that tells whether a page uses this layout
+    fn selector(&self, game: &Game) -> String;
+
+    // once the layout matched, the selector for the part of the page to keep
+    fn section_selector(&self, _page: &Html, game: &Game) -> String {
+        self.selector(game)
+    }
+}
+
+fn persona_selector(game: &Game) -> &'static str {
+    if game.entry == PersonaTitle::P3J || game.entry == PersonaTitle::P3A {
+        P3_SELECTOR
+    } else {
+        P4_SELECTOR
+    }
+}
+
+fn first_tab_id(game: &Game) -> String {
+    game.tab_names.first().map(|t| t.replace(" ", "_")).unwrap_or_default()
+}
+
+// game heading followed by tabs, one per variant
+pub struct Tabbed;
+
+impl Layout for Tabbed {
+    fn name(&self) -> &'static str {
+        "tabbed"
+    }
+
+    fn selector(&self, game: &Game) -> String {
+        format!("{} + .tabber > .tabbertab", persona_selector(game))
+    }
+
+    // pages covering Portable nest another set of tabs inside the version tab
+    fn section_selector(&self, page: &Html, game: &Game) -> String {
+        let version_selector = Selector::parse(self.selector(game).as_str()).unwrap();
+        if page
+            .select(&version_selector)
+            .any(|t| t.value().attr("title").unwrap_or("") == "Portable") {
+            format!("{} + .tabber > .tabbertab > .tabber", persona_selector(game))
+        } else {
+            self.selector(game)
+        }
+    }
+}
+
+// game heading, no tabs
+// https://megamitensei.fandom.com/wiki/Primitive_Idol
+pub struct HeadingTable;
+
+impl Layout for HeadingTable {
+    fn name(&self) -> &'static str {
+        "heading+table"
+    }
+
+    fn selector(&self, game: &Game) -> String {
+        format!("{} + table", persona_selector(game))
+    }
+}
+
+// multiple versions exist (Journey/Answer), but are separate tables instead of tabbed
+// https://megamitensei.fandom.com/wiki/Indolent_Maya
+pub struct SeparateVersionTables;
+
+impl Layout for SeparateVersionTables {
+    fn name(&self) -> &'static str {
+        "separate version tables"
+    }
+
+    fn selector(&self, game: &Game) -> String {
+        format!("{} ~ #{} + table", persona_selector(game), first_tab_id(game))
+    }
+}
+
+// shadow only has appearance in one game, and the html does not have sections for games
+pub struct TabberOnly;
+
+impl Layout for TabberOnly {
+    fn name(&self) -> &'static str {
+        "tabber only"
+    }
+
+    fn selector(&self, _game: &Game) -> String {
+        ".tabber".to_string()
+    }
+}
+
+// version heading with a (sometimes misspelled) id right before the table
+// https://megamitensei.fandom.com/wiki/Conceited_Maya
+pub struct IdPrefixed;
+
+impl Layout for IdPrefixed {
+    fn name(&self) -> &'static str {
+        "id-prefixed"
+    }
+
+    fn selector(&self, game: &Game) -> String {
+        format!("[id^={}] + table", first_tab_id(game))
+    }
+}
+
+// table exists, no tabs or identifiers
+pub struct BareStatsTable;
+
+impl Layout for BareStatsTable {
+    fn name(&self) -> &'static str {
+        "bare stats table"
+    }
+
+    fn selector(&self, _game: &Game) -> String {
+        "#Stats-collapsible-section > table:nth-child(1)".to_string()
+    }
+}
+
+pub fn default_layouts() -> Vec<Box<dyn Layout>> {
+    vec![
+        Box::new(Tabbed),
+        Box::new(HeadingTable),
+        Box::new(SeparateVersionTables),
+        Box::new(TabberOnly),
+        Box::new(IdPrefixed),
+        Box::new(BareStatsTable)
+    ]
+}
+
+pub struct Matched {
+    pub layout: &'static str,
+    pub selector: Selector
+}
+
+// the first layout the page uses, and the selector for its game section
+pub fn match_layout(page: &Html, game: &Game, layouts: &[Box<dyn Layout>]) -> Option<Matched> {
+    for layout in layouts {
+        // tab names end up in selectors, a weird one shouldn't take the whole chain down
+        let selector = match Selector::parse(layout.selector(game).as_str()) {
+            Ok(s) => s,
+            Err(_) => continue
+        };
+
+        if page.select(&selector).next().is_some() {
+            let section = match Selector::parse(layout.section_selector(page, game).as_str()) {
+                Ok(s) => s,
+                Err(_) => continue
+            };
+
+            return Some(Matched {
+                layout: layout.name(),
+                selector: section
+            });
+        }
+    }
+
+    None
+}
@@ -1,13 +1,21 @@
 #[cfg(test)]
 mod test;
+pub mod diagnostics;
+pub mod layouts;
+pub mod wikitext;
 
 use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use titlecase::titlecase;
+use inflector::Inflector;
 use serde::{Deserialize, Serialize};
-use scraper::{Selector, Html};
+use serde::de::DeserializeOwned;
+use scraper::{ElementRef, Selector, Html};
 use std::collections::HashMap;
-use crate::{utils, errors};
-use crate::errors::NoVariantError;
+use std::time::SystemTime;
+use crate::{cache, config, locations, utils, errors};
+use crate::locations::Location;
+use crate::errors::{NoVariantError, TableParseError};
+use self::layouts::Layout;
 
 #[derive(Deserialize, Debug)]
 struct PageMeta {
@@ -20,8 +28,43 @@ struct Query {
 }
 
 #[derive(Deserialize, Debug)]
-struct Page {
-    content: String
+struct PageRevisions {
+    query: PageRevisionsQuery
+}
+
+#[derive(Deserialize, Debug)]
+struct PageRevisionsQuery {
+    pages: HashMap<String, PageRevisionsPage>
+}
+
+#[derive(Deserialize, Debug)]
+struct PageRevisionsPage {
+    #[serde(default)]
+    revisions: Vec<RevisionId>
+}
+
+#[derive(Deserialize, Debug)]
+struct RevisionId {
+    revid: u64
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Page {
+    pub content: String,
+    #[serde(rename = "heroImage")]
+    pub hero_image: Option<HeroImage>,
+    #[serde(default)]
+    pub categories: Vec<Category>
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Category {
+    pub title: String
+}
+
+#[derive(Deserialize, Debug)]
+pub struct HeroImage {
+    pub url: String
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +75,13 @@ pub struct Game {
     pub variant: Option<String>
 }
 
+// Where resistances are read from: the rendered page, or the stat templates in its wikitext
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Backend {
+    Html,
+    Wikitext
+}
+
 #[derive(PartialEq, Copy, Clone, Debug)]
 pub enum PersonaTitle {
     P3J,
@@ -39,19 +89,191 @@ pub enum PersonaTitle {
     P4G
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ShadowInfo {
     pub game: String,
-    #[serde(skip_serializing)]
-    pub version: String,
     pub variant: String,
-    pub resistances: HashMap<String, Vec<String>>
+    pub resistances: HashMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<Stats>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<Skill>,
+    // what was off about the table when only part of it could be read
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>
+}
+
+// None wherever the wiki has "??" or nothing at all
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hp: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sp: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strength: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub magic: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endurance: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agility: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub luck: Option<u32>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Skill {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub effect: String
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Shadow {
     pub name: String,
-    pub info: Vec<ShadowInfo>
+    pub info: Vec<ShadowInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<Location>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub infobox: Option<Infobox>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<Kind>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arcana: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub appearances: Vec<Appearance>,
+    // which page layout the stats were found with, handy for bug reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>
+}
+
+// The page a shadow was scraped from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Source {
+    pub wiki_url: String,
+    pub page_id: isize,
+    // only looked up for dumps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<u64>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Appearance {
+    // "Persona 3", no matter the version
+    pub game: String,
+    // e.g. "FES" or "Golden"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Boss,
+    MiniBoss,
+    Normal
+}
+
+// Why a full dump left a shadow out
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    // the index links to a page that doesn't exist
+    NotFound,
+    NotInGame,
+    // no stats section for the game
+    NoSection,
+    // a stats section without a readable resistance table
+    NoTable,
+    Network,
+    // anything else the wiki sent back that couldn't be read
+    Parse
+}
+
+impl SkipReason {
+    pub fn of(error: &anyhow::Error) -> SkipReason {
+        if error.is::<errors::NetworkError>() {
+            SkipReason::Network
+        } else if error.is::<errors::NoShadowError>() {
+            SkipReason::NotInGame
+        } else if error.is::<NoVariantError>() {
+            SkipReason::NoSection
+        } else if error.is::<TableParseError>() {
+            SkipReason::NoTable
+        } else {
+            SkipReason::Parse
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Skipped {
+    pub name: String,
+    pub reason: SkipReason,
+    pub message: String
+}
+
+// A row of a game's index page
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub name: String,
+    pub arcana: Option<String>,
+    pub level: Option<String>,
+    pub area: Option<String>
+}
+
+// The portable-infobox at the top of every page
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Infobox {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub japanese_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub romaji: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_appearance: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arcana: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub species: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>
+}
+
+impl Stats {
+    // "HP", "1,300" -> hp: Some(1300). Returns whether the label is a stat at all
+    pub fn set(&mut self, label: &str, value: &str) -> bool {
+        let stat = match label.trim().to_lowercase().as_str() {
+            "level" | "lv" => &mut self.level,
+            "hp" => &mut self.hp,
+            "sp" => &mut self.sp,
+            "strength" | "st" => &mut self.strength,
+            "magic" | "ma" => &mut self.magic,
+            "endurance" | "en" => &mut self.endurance,
+            "agility" | "ag" => &mut self.agility,
+            "luck" | "lu" => &mut self.luck,
+            _ => return false
+        };
+
+        *stat = value.replace(',', "").trim().parse().ok();
+        true
+    }
+}
+
+impl ShadowInfo {
+    // resistance tables are keyed by affinity, this goes the other way
+    pub fn affinity(&self, element: &str) -> String {
+        self.resistances.iter()
+            .find(|(_, elements)| elements.iter().any(|e| e.eq_ignore_ascii_case(element)))
+            .map(|(affinity, _)| affinity.clone())
+            .unwrap_or_else(|| "Neutral".to_string())
+    }
 }
 
 const P3_SELECTOR: &str = "#Stats-collapsible-section > [id*=_3]";
@@ -60,13 +282,15 @@ const P4_SELECTOR: &str = "#Stats-collapsible-section > [id*=_4]";
 const P3_ALL_SHADOWS: isize = 2807;
 const P4_ALL_SHADOWS: isize = 12686;
 
+#[allow(clippy::ptr_arg)]
 pub fn get_shadow_page_id(shadow: &String) -> anyhow::Result<isize> {
     // https://megamitensei.fandom.com/api/v1#!/Articles
     // https://megamitensei.fandom.com/api.php?format=json&action=query&redirect=1&titles=Intrepid_Knight
     // https://megamitensei.fandom.com/api/v1/Articles/AsJson?id=
 
     let page_id_endpoint = format!(
-        "https://megamitensei.fandom.com/api.php?format=json&action=query&redirect=1&titles={}&indexpageids",
+        "{}/api.php?format=json&action=query&redirect=1&titles={}&indexpageids",
+        config::wiki_url(),
         titlecase(shadow.as_str())
     );
     const FRAGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').add(b'`');
@@ -74,257 +298,912 @@ pub fn get_shadow_page_id(shadow: &String) -> anyhow::Result<isize> {
     let page_meta_encoded = utf8_percent_encode(page_id_endpoint.as_str(), FRAGMENT);
     let page_meta: String = page_meta_encoded.collect();
 
-    let body: PageMeta = reqwest::blocking::get(&page_meta)?.json()?;
+    let body: PageMeta = fetch_json(&page_meta)?;
     let id = body.query.pageids[0].parse::<isize>()?;
 
     Ok(id)
 }
 
 pub fn page_html(page_id: &isize) -> anyhow::Result<Html> {
-    let page_endpoint = format!(
-        "https://megamitensei.fandom.com/api/v1/Articles/AsJson?id={}",
-        page_id
-    );
-    let body: Page = reqwest::blocking::get(&page_endpoint)?.json()?;
+    let body = page(page_id)?;
     // println!("{:#?}", body.content);
 
     let document = Html::parse_fragment(body.content.as_str());
     Ok(document)
 }
 
+// The revision that was current when page() fetched the content, which is older than the
+// latest one when the page was edited after it got cached
+pub fn revision_id(page_id: &isize) -> anyhow::Result<Option<u64>> {
+    let fetched_at = cache::written_at(&page_endpoint(page_id)).unwrap_or_else(SystemTime::now);
+    let revisions_endpoint = format!(
+        "{}/api.php?format=json&action=query&prop=revisions&rvprop=ids&rvlimit=1&rvdir=older&rvstart={}&pageids={}",
+        config::wiki_url(),
+        humantime::format_rfc3339_seconds(fetched_at),
+        page_id
+    );
+
+    let body: PageRevisions = fetch_json(&revisions_endpoint)?;
+    Ok(body.query.pages.into_values().find_map(|p| p.revisions.first().map(|r| r.revid)))
+}
+
+fn page_endpoint(page_id: &isize) -> String {
+    format!("{}/api/v1/Articles/AsJson?id={}", config::wiki_url(), page_id)
+}
+
+// The whole AsJson response, for when more than the content is needed
+pub fn page(page_id: &isize) -> anyhow::Result<Page> {
+    fetch_json(&page_endpoint(page_id))
+}
+
+pub fn infobox(page: &Html, hero_image: Option<&HeroImage>) -> Option<Infobox> {
+    let item_selector = Selector::parse(".portable-infobox .pi-data").unwrap();
+    let value_selector = Selector::parse(".pi-data-value").unwrap();
+    let link_selector = Selector::parse("a").unwrap();
+
+    let mut infobox = Infobox {
+        image: hero_image.map(|i| i.url.clone()),
+        ..Infobox::default()
+    };
+
+    for item in page.select(&item_selector) {
+        let value = match item.select(&value_selector).next() {
+            Some(v) => v,
+            None => continue
+        };
+        let text = value.text().collect::<String>().trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+
+        match item.value().attr("data-source").unwrap_or("") {
+            "japan" => infobox.japanese_name = Some(text),
+            "romaji" => infobox.romaji = Some(text),
+            "first appearance" => infobox.first_appearance = Some(text),
+            "species" => infobox.species = Some(text),
+            "arcana" => {
+                // several arcana are either separate links or written out with slashes
+                let links: Vec<String> = value.select(&link_selector)
+                    .map(|a| a.text().collect::<String>().trim().to_string())
+                    .filter(|a| !a.is_empty())
+                    .collect();
+
+                infobox.arcana = if links.is_empty() {
+                    text.split(['/', ',']).map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect()
+                } else {
+                    links
+                };
+            },
+            _ => {}
+        }
+    }
+
+    if infobox == Infobox::default() {
+        None
+    } else {
+        Some(infobox)
+    }
+}
+
+// Same as page_html, for an AsJson response that's already been fetched
+#[cfg(test)]
+pub fn parse_page(body: &str) -> anyhow::Result<Html> {
+    let page: Page = serde_json::from_str(body)?;
+    Ok(Html::parse_fragment(page.content.as_str()))
+}
+
+// responses are only cached once they deserialize, so a bad response never sticks around
+fn fetch_json<T: DeserializeOwned>(url: &str) -> anyhow::Result<T> {
+    config::check()?;
+    if let Some(body) = cache::read(url) {
+        if let Ok(parsed) = serde_json::from_str(&body) {
+            return Ok(parsed);
+        }
+    }
+
+    // an error page from a wiki that's down or rate limiting is a network failure, not a bad response
+    let body = reqwest::blocking::get(url)
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.text())
+        .map_err(|e| errors::NetworkError { url: url.to_string(), reason: e.to_string() })?;
+    let parsed = serde_json::from_str(&body)
+        .map_err(|e| errors::ParseError { url: url.to_string(), reason: e.to_string() })?;
+    cache::write(url, &body);
+
+    Ok(parsed)
+}
+
+pub fn get_shadow(name: &str, game: &Game) -> anyhow::Result<Shadow> {
+    get_shadow_with(name, game, Backend::Html)
+}
+
+pub fn get_shadow_with(name: &str, game: &Game, backend: Backend) -> anyhow::Result<Shadow> {
+    let mut shadow = Shadow {
+        name: name.to_title_case(),
+        info: vec![],
+        locations: vec![],
+        infobox: None,
+        categories: vec![],
+        kind: None,
+        arcana: vec![],
+        appearances: vec![],
+        layout: None,
+        source: None
+    };
+
+    let page_id = get_shadow_page_id(&name.to_string())?;
+    if page_id == -1 {
+        return Err(errors::NoShadowError {
+            name: shadow.name,
+            game: game.entry_text.clone()
+        }.into());
+    }
+
+    shadow.source = Some(Source {
+        wiki_url: config::wiki_url().to_string(),
+        page_id,
+        revision_id: None
+    });
+
+    let response = page(&page_id)?;
+    let page = Html::parse_fragment(response.content.as_str());
+    if !appears_in_categories(&response.categories, game).map_or_else(|| appears_in(&page, game), Ok)? {
+        return Err(errors::NoShadowError {
+            name: shadow.name,
+            game: game.entry_text.clone()
+        }.into());
+    }
+
+    let (info, layout) = resistances(&page, &page_id, game, &shadow.name, backend)?;
+    shadow.info = info;
+    shadow.layout = Some(layout.to_string());
+
+    add_page_details(&mut shadow, &page, &response, game);
+
+    Ok(shadow)
+}
+
+// every variant's resistances, and the layout they were found with
+fn resistances(
+    page: &Html,
+    page_id: &isize,
+    game: &Game,
+    shadow_name: &str,
+    backend: Backend
+) -> anyhow::Result<(Vec<ShadowInfo>, &'static str)> {
+    match backend {
+        Backend::Html => {
+            let (subsection, layout) = game_section(page, game, shadow_name.to_string())?;
+            let table_nodes = game_table(&subsection)?;
+            let mut info = extract_tables(&table_nodes, game, shadow_name)?;
+            add_details(&mut info, &subsection);
+            Ok((info, layout))
+        },
+        Backend::Wikitext => {
+            let text = wikitext::page_wikitext(page_id)?;
+            Ok((wikitext::shadow_info(&text, game, shadow_name)?, "wikitext"))
+        }
+    }
+}
+
+// everything besides the resistance tables
+fn add_page_details(shadow: &mut Shadow, page: &Html, response: &Page, game: &Game) {
+    if let Some(profile) = locations::profile_text(page, game) {
+        shadow.locations = locations::parse_locations(&profile, game);
+    }
+    shadow.infobox = infobox(page, response.hero_image.as_ref());
+    shadow.appearances = appearances(page);
+    shadow.categories = response.categories.iter().map(|c| c.title.clone()).collect();
+
+    let (kind, arcana) = classify(&shadow.categories, game);
+    shadow.kind = kind;
+    shadow.arcana = if arcana.is_empty() {
+        shadow.infobox.as_ref().map(|i| i.arcana.clone()).unwrap_or_default()
+    } else {
+        arcana
+    };
+}
+
+// the releases of each mainline game, anything else after the game's name is a spin-off
+// like Persona 4 Arena or Persona 3 Dancing in Moonlight
+const GAME_VERSIONS: &[(&str, &[&str])] = &[
+    ("Persona 3", &["FES", "The Answer", "Portable", "Reload"]),
+    ("Persona 4", &["Golden"]),
+    ("Persona 5", &["Royal"])
+];
+
+const CATEGORY_KINDS: &[&str] = &["Shadows", "Enemies", "Bosses", "Mini-bosses", "Sub-bosses"];
+
+fn game_versions(game: &str) -> &'static [&'static str] {
+    GAME_VERSIONS.iter()
+        .find(|(g, _)| *g == game)
+        .map_or(&[], |(_, versions)| versions)
+}
+
+// "Persona 4 Shadows" or "Persona 4 Golden Bosses", but not "Persona 4 Arena Shadows". The
+// inner option is the version, for categories that name one
+fn game_category(title: &str, game: &str) -> Option<Option<&'static str>> {
+    let rest = title.strip_prefix(game)?.strip_prefix(' ')?;
+    let (version, rest) = game_versions(game).iter()
+        .find_map(|v| rest.strip_prefix(v).and_then(|r| r.strip_prefix(' ')).map(|r| (Some(*v), r)))
+        .unwrap_or((None, rest));
+
+    if CATEGORY_KINDS.iter().any(|k| rest.eq_ignore_ascii_case(k)) {
+        Some(version)
+    } else {
+        None
+    }
+}
+
+fn is_game_category(title: &str, game: &str) -> bool {
+    game_category(title, game).is_some()
+}
+
+// The Answer only exists in FES, but plenty of pages just say "Persona 3" without any
+// versions, in which case there's no telling
+fn answer_versions(versions: &[Option<&str>]) -> Option<bool> {
+    if versions.iter().any(|v| v.is_some()) {
+        Some(versions.iter().any(|v| matches!(v, Some("FES") | Some("The Answer"))))
+    } else {
+        None
+    }
+}
+
+// Categories look like "Persona 3 Bosses", "Persona 4 Golden Shadows" or "Magician Arcana".
+// Whether a shadow is a boss depends on the game, Crying Table is one in 3 but not in 4
+pub fn classify(categories: &[String], game: &Game) -> (Option<Kind>, Vec<String>) {
+    let arcana = categories.iter()
+        .filter_map(|c| c.strip_suffix(" Arcana"))
+        .map(|a| a.to_string())
+        .collect();
+
+    let game_categories: Vec<String> = categories.iter()
+        .filter(|c| is_game_category(c, &game.entry_text))
+        .map(|c| c.to_lowercase())
+        .collect();
+
+    let kind = if game_categories.is_empty() {
+        None
+    } else if game_categories.iter().any(|c| c.ends_with("mini-bosses") || c.ends_with("sub-bosses")) {
+        Some(Kind::MiniBoss)
+    } else if game_categories.iter().any(|c| c.ends_with("bosses")) {
+        Some(Kind::Boss)
+    } else {
+        Some(Kind::Normal)
+    };
+
+    (kind, arcana)
+}
+
+// Categories are maintained by the wiki's editors and a lot more consistent than the
+// Appearances section. None when the page has no game categories at all
+pub fn appears_in_categories(categories: &[Category], game: &Game) -> Option<bool> {
+    let games: Vec<&String> = categories.iter()
+        .map(|c| &c.title)
+        .filter(|t| t.starts_with("Persona "))
+        .collect();
+
+    if games.is_empty() {
+        return None;
+    }
+
+    let versions: Vec<Option<&str>> = games.iter()
+        .filter_map(|t| game_category(t, &game.entry_text))
+        .collect();
+    if game.entry == PersonaTitle::P3A {
+        if let Some(in_answer) = answer_versions(&versions) {
+            return Some(in_answer);
+        }
+    }
+
+    Some(!versions.is_empty())
+}
+
 // determine if shadow appears only in 1 game, changing the base selector
 // yea, seriously, this was the best way I could think of given the html that comes back
 pub fn appears_in(page: &Html, entry: &Game) -> anyhow::Result<bool> {
-    // weird selector right?
-    // https://megamitensei.fandom.com/wiki/Bigoted_Maya
-    // note the 'Appearaces' section
-    // in addition to having an unpredictable page structure, seems i can't even get
-    // a guarantee things will be spelled correctly
-    // but wait! it gets better:
-    // https://megamitensei.fandom.com/wiki/Desirous_Maya
-    let appearances_section = Selector::parse("[id^=Appe] > ul > li > i").unwrap();
+    let in_game: Vec<Appearance> = appearances(page).into_iter()
+        .filter(|a| a.game == entry.entry_text)
+        .collect();
 
-    let mut all_appearances = "".to_string();
-    for element in page.select(&appearances_section) {
-        let mut appearance = element.text().collect::<String>();
-        appearance.retain(|c| !c.is_whitespace());
-        all_appearances += &appearance;
+    if entry.entry == PersonaTitle::P3A {
+        let versions: Vec<Option<&str>> = in_game.iter().map(|a| a.version.as_deref()).collect();
+        if let Some(in_answer) = answer_versions(&versions) {
+            return Ok(in_answer);
+        }
     }
-    all_appearances = all_appearances.replace("/", "");
 
-    let entry_trimmed = entry.entry_text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
-    if all_appearances.contains(&entry_trimmed) {
-        return Ok(true);
+    Ok(!in_game.is_empty())
+}
+
+// weird, right?
+// https://megamitensei.fandom.com/wiki/Bigoted_Maya
+// note the 'Appearaces' section
+// in addition to having an unpredictable page structure, seems i can't even get
+// a guarantee things will be spelled correctly
+// but wait! it gets better:
+// https://megamitensei.fandom.com/wiki/Desirous_Maya
+pub fn appearances(page: &Html) -> Vec<Appearance> {
+    let id_selector = Selector::parse("[id]").unwrap();
+    let item_selector = Selector::parse("ul > li").unwrap();
+
+    let section = page.select(&id_selector).find(|e| {
+        let id = e.value().id().unwrap_or("");
+        let name = id.trim_end_matches("-collapsible-section").replace('_', " ").to_lowercase();
+
+        (name.starts_with("appe") || utils::edit_distance(&name, "appearances") <= 2)
+            && e.select(&item_selector).next().is_some()
+    });
+
+    let mut found: Vec<Appearance> = vec![];
+    if let Some(section) = section {
+        for item in section.select(&item_selector) {
+            let text = item.text().collect::<String>();
+            for title in text.split(['/', ',']) {
+                if let Some(appearance) = parse_appearance(title) {
+                    if !found.contains(&appearance) {
+                        found.push(appearance);
+                    }
+                }
+            }
+        }
     }
 
-    Ok(false)
+    found
 }
 
-pub fn arcana_sections(game: &Game) -> anyhow::Result<Vec<Shadow>> {
-    let table_selector = Selector::parse(".table > tbody > tr td:nth-child(1)").unwrap();
-    let page_id = if game.entry == PersonaTitle::P3J || game.entry == PersonaTitle::P3A {
+// "Persona 3 FES", "Persona3 Portable", "P4G" and the like
+pub fn parse_appearance(title: &str) -> Option<Appearance> {
+    let title = title.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let title = title.trim_matches(|c: char| !c.is_alphanumeric());
+    if title.is_empty() {
+        return None;
+    }
+
+    let compact = title.replace(' ', "").to_lowercase();
+    let abbreviated = match compact.as_str() {
+        "p3" => Some(("3", None)),
+        "p3fes" => Some(("3", Some("FES"))),
+        "p3p" => Some(("3", Some("Portable"))),
+        "p3r" => Some(("3", Some("Reload"))),
+        "p4" => Some(("4", None)),
+        "p4g" => Some(("4", Some("Golden"))),
+        _ => None
+    };
+    if let Some((number, version)) = abbreviated {
+        return Some(Appearance {
+            game: format!("Persona {}", number),
+            version: version.map(|v| v.to_string())
+        });
+    }
+
+    // "Persona" then the series number, with or without a space in between
+    if compact.starts_with("persona") {
+        let rest = title["persona".len()..].trim_start();
+        let number: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+
+        if !number.is_empty() {
+            let game = format!("Persona {}", number);
+            let version = rest[number.len()..].trim_matches(|c: char| c.is_whitespace() || c == ':' || c == '-');
+            if version.is_empty() {
+                return Some(Appearance { game, version: None });
+            }
+
+            // only the game's own releases, "Persona 4 Arena" is a game of its own
+            if let Some(v) = game_versions(&game).iter().find(|v| v.eq_ignore_ascii_case(version)) {
+                return Some(Appearance { game, version: Some(v.to_string()) });
+            }
+        }
+    }
+
+    Some(Appearance {
+        game: title.to_string(),
+        version: None
+    })
+}
+
+fn index_page_id(game: &Game) -> isize {
+    if game.entry == PersonaTitle::P3J || game.entry == PersonaTitle::P3A {
         P3_ALL_SHADOWS
     } else {
         P4_ALL_SHADOWS
-    };
+    }
+}
+
+// every shadow name listed on the game's index page
+pub fn shadow_names(game: &Game) -> anyhow::Result<Vec<String>> {
+    let table_selector = Selector::parse(".table > tbody > tr td:nth-child(1)").unwrap();
+    let page_id = index_page_id(game);
     let page = page_html(&page_id)?;
 
-    let mut all_shadows: Vec<Shadow> = vec![];
+    let names: Vec<String> = page.select(&table_selector).map(|e| e.text().collect::<String>()).collect();
+    cache::write(&names_key(page_id), &names.join("\n"));
 
-    for element in page.select(&table_selector) {
-        let shadow_name = &element.text().collect::<String>();
-        let page_id = get_shadow_page_id(&shadow_name)?;
-        let page_html = page_html(&page_id)?;
+    Ok(names)
+}
 
-        let mut current_shadow = Shadow {
-            name: shadow_name.clone(),
-            info: vec![]
-        };
+fn names_key(index_page_id: isize) -> String {
+    format!("{}/shadow names/{}", config::wiki_url(), index_page_id)
+}
 
-        let appears_in = appears_in(&page_html, &game)?;
-        if !appears_in {
-            let no_shadow_err = errors::NoShadowError {
-                name: shadow_name.clone(),
-                game: game.entry_text.clone()
+// The names from the last time the index was read, shell completion asks for them on every tab
+pub fn cached_shadow_names(game: &Game) -> anyhow::Result<Vec<String>> {
+    match cache::read(&names_key(index_page_id(game))) {
+        Some(names) => Ok(names.lines().map(|n| n.to_string()).collect()),
+        None => shadow_names(game)
+    }
+}
+
+// Everything the index page says about the game's shadows, without fetching their pages
+pub fn shadow_index(game: &Game) -> anyhow::Result<Vec<IndexEntry>> {
+    Ok(parse_index(&page_html(&index_page_id(game))?))
+}
+
+const ARCANA: &[&str] = &[
+    "Fool", "Magician", "Priestess", "Empress", "Emperor", "Hierophant", "Lovers", "Chariot",
+    "Justice", "Hermit", "Fortune", "Strength", "Hanged Man", "Death", "Temperance", "Devil",
+    "Tower", "Star", "Moon", "Sun", "Judgement", "Judgment", "Aeon", "Jester", "World"
+];
+
+// The index has a table per arcana, each under an h2 named after it, with h3s like "Bosses"
+// splitting some of them up. Rows naming a shadow link to its page, columns besides the
+// name differ between tables, level and area are kept when a table has them
+pub fn parse_index(page: &Html) -> Vec<IndexEntry> {
+    let selector = Selector::parse("h2, table.table").unwrap();
+    let headline_selector = Selector::parse(".mw-headline").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+
+    let mut entries = vec![];
+    let mut arcana = None;
+    for element in page.select(&selector) {
+        if element.value().name() == "h2" {
+            let title = match element.select(&headline_selector).next() {
+                Some(headline) => cell_text(&headline),
+                None => cell_text(&element)
             };
-            eprintln!("{}", no_shadow_err);
+            let title = title.trim_end_matches("Arcana").trim();
+            arcana = ARCANA.iter()
+                .find(|a| a.eq_ignore_ascii_case(title))
+                .map(|a| a.to_string());
             continue;
         }
 
-        let subsection = match game_section(&page_html, &game, shadow_name.clone()) {
-            Ok(s) => s,
-            Err(e) => {
-                eprintln!("{}", e);
+        let mut level_column = None;
+        let mut area_column = None;
+        for row in table_rows(element) {
+            let cells = row_cells(row);
+            if cells.iter().all(|c| c.value().name() == "th") {
+                let position = |names: &[&str]| cells.iter()
+                    .position(|c| names.iter().any(|n| cell_text(c).eq_ignore_ascii_case(n)));
+                level_column = position(&["Level", "Lv", "Lv."]);
+                area_column = position(&["Area", "Location", "Dungeon", "Block"]);
                 continue;
             }
+
+            // rows without a link to a shadow's page are notes
+            if cells.first().and_then(|c| c.select(&link_selector).next()).is_none() {
+                continue;
+            }
+            let cells: Vec<String> = cells.iter().map(cell_text).collect();
+            let column = |idx: Option<usize>| idx
+                .and_then(|i| cells.get(i))
+                .filter(|c| !c.is_empty())
+                .cloned();
+
+            if let Some(name) = cells.first().filter(|n| !n.is_empty()) {
+                entries.push(IndexEntry {
+                    name: name.clone(),
+                    arcana: arcana.clone(),
+                    level: column(level_column),
+                    area: column(area_column)
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+// For the command line, the skipped shadows are printed once everything's fetched
+pub fn arcana_sections(game: &Game) -> anyhow::Result<Vec<Shadow>> {
+    let (shadows, skipped) = arcana_sections_with(game, Backend::Html)?;
+    print_skipped(&skipped);
+
+    Ok(shadows)
+}
+
+pub fn print_skipped(skipped: &[Skipped]) {
+    for s in skipped {
+        eprintln!("{}", s.message);
+    }
+}
+
+pub fn arcana_sections_with(game: &Game, backend: Backend) -> anyhow::Result<(Vec<Shadow>, Vec<Skipped>)> {
+    arcana_sections_progress(game, backend, &mut |_, _| {})
+}
+
+// Every shadow on the index that could be read, and why the others couldn't. Only an
+// unreachable index stops the whole thing. Nothing is printed, progress gets (done, total)
+// before each page so a caller owning the terminal can show it
+pub fn arcana_sections_progress(
+    game: &Game,
+    backend: Backend,
+    progress: &mut dyn FnMut(usize, usize)
+) -> anyhow::Result<(Vec<Shadow>, Vec<Skipped>)> {
+    let mut all_shadows: Vec<Shadow> = vec![];
+    let mut skipped: Vec<Skipped> = vec![];
+
+    let names = shadow_names(game)?;
+    for (done, shadow_name) in names.iter().enumerate() {
+        progress(done, names.len());
+        let mut skip = |reason: SkipReason, message: String| {
+            skipped.push(Skipped {
+                name: shadow_name.clone(),
+                reason,
+                message
+            });
         };
 
-        let table_nodes = match game_table(&subsection) {
-            Ok(t) => t,
+        let page_id = match get_shadow_page_id(shadow_name) {
+            Ok(-1) => {
+                skip(SkipReason::NotFound, format!("No wiki page for: {}", shadow_name));
+                continue;
+            },
+            Ok(id) => id,
             Err(e) => {
-                eprintln!("{}", e);
+                skip(SkipReason::of(&e), e.to_string());
                 continue;
             }
         };
+        let response = match page(&page_id) {
+            Ok(r) => r,
+            Err(e) => {
+                skip(SkipReason::of(&e), e.to_string());
+                continue;
+            }
+        };
+        let page_html = Html::parse_fragment(response.content.as_str());
+
+        let mut current_shadow = Shadow {
+            name: shadow_name.clone(),
+            info: vec![],
+            locations: vec![],
+            infobox: None,
+            categories: vec![],
+            kind: None,
+            arcana: vec![],
+            appearances: vec![],
+            layout: None,
+            source: None
+        };
+
+        let appears_in = appears_in_categories(&response.categories, game)
+            .map_or_else(|| appears_in(&page_html, game), Ok);
+        match appears_in {
+            Ok(true) => {},
+            Ok(false) => {
+                let no_shadow_err = errors::NoShadowError {
+                    name: shadow_name.clone(),
+                    game: game.entry_text.clone()
+                };
+                skip(SkipReason::NotInGame, no_shadow_err.to_string());
+                continue;
+            },
+            Err(e) => {
+                skip(SkipReason::of(&e), e.to_string());
+                continue;
+            }
+        }
 
-        for (table, variant) in table_nodes {
-            current_shadow.info.push(extract_table_data(&table, &variant, &game)?);
+        current_shadow.source = Some(Source {
+            wiki_url: config::wiki_url().to_string(),
+            page_id,
+            // a dump is still useful without revisions, don't give up on the shadow over one
+            revision_id: revision_id(&page_id).unwrap_or(None)
+        });
+
+        match resistances(&page_html, &page_id, game, shadow_name, backend) {
+            Ok((info, layout)) => {
+                current_shadow.info = info;
+                current_shadow.layout = Some(layout.to_string());
+            },
+            Err(e) => {
+                skip(SkipReason::of(&e), e.to_string());
+                continue;
+            }
         }
 
+        add_page_details(&mut current_shadow, &page_html, &response, game);
+
         all_shadows.push(current_shadow);
         // println!("{}", serde_json::to_string(&current_shadow)?);
     }
 
-    Ok(all_shadows)
+    Ok((all_shadows, skipped))
 }
 
-pub fn game_section(page: &Html, game: &Game, shadow_name: String) -> anyhow::Result<Html> {
-    let persona_selector = if game.entry == PersonaTitle::P3J || game.entry == PersonaTitle::P3A {
-        P3_SELECTOR
-    } else {
-        P4_SELECTOR
-    };
+// the game's part of the stats section, and the name of the layout it was found with
+pub fn game_section(page: &Html, game: &Game, shadow_name: String) -> anyhow::Result<(Html, &'static str)> {
+    game_section_with(page, game, shadow_name, &layouts::default_layouts())
+}
 
-    let mut base_selector = Selector::parse(format!("{} + .tabber > .tabbertab", persona_selector).as_str()).unwrap();
-
-    // TODO there's gotta be a better way to do this
-    let selector = match page.select(&base_selector).count() {
-        0 => {
-            // case when no tabs
-            base_selector = Selector::parse(format!("{} + table", persona_selector).as_str()).unwrap();
-
-            match page.select(&base_selector).count() {
-                0 => {
-                    // case when multiple versions exist (Journey/Answer), but are separate
-                    // tables instead of tabbed
-                    // https://megamitensei.fandom.com/wiki/Indolent_Maya
-                    base_selector = Selector::parse(
-                        format!(
-                            "{} ~ #{} + table",
-                            persona_selector,
-                            game.tab_names.first().unwrap().replace(" ", "_")).as_str()
-                    ).unwrap();
-
-                    match page.select(&base_selector).count() {
-                        // case when shadow only has appearance in one game, and the html
-                        // does not have sections for games
-                        0 => {
-                            base_selector = Selector::parse(".tabber").unwrap();
-
-                            match page.select(&base_selector).count() {
-                                0 => {
-                                    base_selector = Selector::parse(
-                                        format!(
-                                            "[id^={}] + table",
-                                            game.tab_names.first().unwrap().replace(" ", "_")).as_str()
-                                    ).unwrap();
-
-                                    match page.select(&base_selector).count() {
-                                        0 => {
-                                            // case when table exists, no tabs or identifiers
-                                            base_selector = Selector::parse(
-                                                "#Stats-collapsible-section > table:nth-child(1)"
-                                            ).unwrap();
-
-                                            match page.select(&base_selector).count() {
-                                                0 => return Err(NoVariantError {
-                                                    shadow_name,
-                                                    game: game.entry_text.clone(),
-                                                    variant: game.tab_names.clone()
-                                                }.into()),
-                                                _ => base_selector
-                                            }
-                                        }
-                                        _ => base_selector
-                                    }
-                                },
-                                _ => base_selector
-                            }
-                        },
-                        _ => base_selector
-                    }
-                },
-                _ => base_selector
-            }
-        },
-        _ => {
-            let version_selector = Selector::parse(format!("{} + .tabber > .tabbertab", persona_selector).as_str()).unwrap();
-            if page
-                .select(&version_selector)
-                .any(|t| t.value().attr("title").unwrap_or("").to_string() == "Portable".to_string()) {
-                Selector::parse(format!("{} + .tabber > .tabbertab > .tabber", persona_selector).as_str()).unwrap()
-            } else {
-                base_selector
-            }
-        }
+// Same as game_section, trying the given layouts in order
+pub fn game_section_with(
+    page: &Html,
+    game: &Game,
+    shadow_name: String,
+    layouts: &[Box<dyn Layout>]
+) -> anyhow::Result<(Html, &'static str)> {
+    let matched = match layouts::match_layout(page, game, layouts) {
+        Some(m) => m,
+        None => return Err(NoVariantError {
+            shadow_name,
+            game: game.entry_text.clone(),
+            variant: game.tab_names.clone()
+        }.into())
     };
 
-    let subsection_sel =  page.select(&selector);
+    let subsection_sel =  page.select(&matched.selector);
     let subsection = Html::parse_fragment(subsection_sel.map(|n| n.html())
         .collect::<String>().as_str());
 
-    Ok(subsection)
+    Ok((subsection, matched.layout))
 }
 
-pub fn game_table(doc: &Html) -> anyhow::Result<Vec<(Html, String)>> {
+// the tabs holding each variant's tables, None when the section has no tabs
+fn variant_tabs(doc: &Html) -> Option<Selector> {
     // this silliness is required because, sometimes, RARELY, there's nested tabs
     // even within a game's section
     // https://megamitensei.fandom.com/wiki/Green_Sigil#Persona%203
     let mut tabs = Selector::parse(".tabbertab > .tabber > .tabbertab").unwrap();
 
-    let mut results = vec![];
-
     // if the nested tabs don't exist, set selector properly
     if doc.select(&tabs).count() < 1 {
         tabs = Selector::parse(".tabbertab").unwrap();
     }
 
     if doc.select(&tabs).count() < 1 {
-        let variant = "Default".to_string();
-        let tab_selector = Selector::parse("table:nth-child(1) > tbody > tr > td > table:nth-child(1) > tbody > tr > td > table:nth-child(2)").unwrap();
-        let table_node = doc.select(&tab_selector);
-        let resistance_table = Html::parse_fragment(table_node.map(|n| n.html())
-            .collect::<String>().as_str());
-
-        results.push((resistance_table, variant));
+        None
     } else {
-        for (idx, elem) in doc.select(&tabs).enumerate() {
-            let variant = elem.value().attr("title").unwrap().to_string();
-            let tab_selector = gen_table_selector(&idx);
+        Some(tabs)
+    }
+}
+
+pub fn game_table(doc: &Html) -> anyhow::Result<Vec<(Html, String)>> {
+    let mut results = vec![];
+
+    match variant_tabs(doc) {
+        None => {
+            let variant = "Default".to_string();
+            let tab_selector = Selector::parse("table:nth-child(1) > tbody > tr > td > table:nth-child(1) > tbody > tr > td > table:nth-child(2)").unwrap();
             let table_node = doc.select(&tab_selector);
             let resistance_table = Html::parse_fragment(table_node.map(|n| n.html())
                 .collect::<String>().as_str());
 
             results.push((resistance_table, variant));
+        },
+        Some(tabs) => {
+            for (idx, elem) in doc.select(&tabs).enumerate() {
+                let variant = elem.value().attr("title").unwrap().to_string();
+                let tab_selector = gen_table_selector(&idx);
+                let table_node = doc.select(&tab_selector);
+                let resistance_table = Html::parse_fragment(table_node.map(|n| n.html())
+                    .collect::<String>().as_str());
+
+                results.push((resistance_table, variant));
+            }
         }
     }
 
     Ok(results)
 }
 
+// Stats and skills for each variant, found by what their tables contain rather than where
+// they are, the resistance table selectors are already fragile enough
+pub fn game_details(doc: &Html) -> Vec<(String, Option<Stats>, Vec<Skill>)> {
+    let table_selector = Selector::parse("table").unwrap();
+    let details = |tab: ElementRef| {
+        let tables: Vec<ElementRef> = tab.select(&table_selector).collect();
+        let stats = tables.iter().find_map(|t| parse_stats(*t));
+        let skills = tables.iter().map(|t| parse_skills(*t)).find(|s| !s.is_empty()).unwrap_or_default();
+        (stats, skills)
+    };
+
+    match variant_tabs(doc) {
+        None => {
+            let (stats, skills) = details(doc.root_element());
+            vec![("Default".to_string(), stats, skills)]
+        },
+        Some(tabs) => doc.select(&tabs)
+            .map(|tab| {
+                let (stats, skills) = details(tab);
+                (tab.value().attr("title").unwrap_or("").to_string(), stats, skills)
+            })
+            .collect()
+    }
+}
+
+// the table's own rows, not those of tables nested in it
+fn table_rows(table: ElementRef) -> Vec<ElementRef> {
+    table.children()
+        .filter_map(ElementRef::wrap)
+        .flat_map(|c| if c.value().name() == "tr" {
+            vec![c]
+        } else {
+            c.children().filter_map(ElementRef::wrap).filter(|r| r.value().name() == "tr").collect()
+        })
+        .collect()
+}
+
+// th and td directly in a row, not the ones of tables nested in it
+fn row_cells(row: ElementRef) -> Vec<ElementRef> {
+    row.children()
+        .filter_map(ElementRef::wrap)
+        .filter(|c| c.value().name() == "th" || c.value().name() == "td")
+        .collect()
+}
+
+fn cell_text(cell: &ElementRef) -> String {
+    cell.text().collect::<String>().trim().to_string()
+}
+
+// The stats table has Arcana/Level/HP/SP headers over their values, and a nested table
+// of Strength, Magic and so on next to them
+fn parse_stats(table: ElementRef) -> Option<Stats> {
+    let row_selector = Selector::parse("tr").unwrap();
+    let rows: Vec<ElementRef> = table.select(&row_selector).collect();
+
+    let header_idx = rows.iter().position(|r| {
+        row_cells(*r).iter().any(|c| c.value().name() == "th" && cell_text(c).eq_ignore_ascii_case("level"))
+    })?;
+
+    let mut stats = Stats::default();
+    let header = rows[header_idx];
+    let labels: Vec<String> = row_cells(header).iter()
+        .filter(|c| c.value().name() == "th")
+        .map(cell_text)
+        .collect();
+    // the nested table's rows come between the two in document order
+    let values = header.next_siblings()
+        .filter_map(ElementRef::wrap)
+        .find(|r| r.value().name() == "tr");
+    if let Some(values) = values {
+        for (label, value) in labels.iter().zip(row_cells(values)) {
+            stats.set(label, &cell_text(&value));
+        }
+    }
+
+    for row in &rows {
+        if let [label, value, ..] = row_cells(*row).as_slice() {
+            if label.value().name() == "td" {
+                stats.set(&cell_text(label), &cell_text(value));
+            }
+        }
+    }
+
+    Some(stats)
+}
+
+// rows of a skill name header followed by its effect
+fn parse_skills(table: ElementRef) -> Vec<Skill> {
+    let row_selector = Selector::parse("tr").unwrap();
+
+    table.select(&row_selector)
+        .filter_map(|row| match row_cells(row).as_slice() {
+            [name, effect, ..] if name.value().name() == "th" && effect.value().name() == "td" => Some(Skill {
+                name: cell_text(name),
+                effect: cell_text(effect)
+            }),
+            _ => None
+        })
+        .collect()
+}
+
+// fills in each variant's stats and skills from the tabs they were found in
+fn add_details(info: &mut [ShadowInfo], doc: &Html) {
+    let details = game_details(doc);
+    for i in info {
+        if let Some((_, stats, skills)) = details.iter().find(|(variant, _, _)| *variant == i.variant) {
+            i.stats = stats.clone();
+            i.skills = skills.clone();
+        }
+    }
+}
+
+// Every variant's table that could be read. A broken table only gets a warning as long as
+// another variant made it, otherwise its error is returned
+pub fn extract_tables(tables: &[(Html, String)], game: &Game, shadow_name: &str) -> anyhow::Result<Vec<ShadowInfo>> {
+    let mut info = vec![];
+    let mut failures = vec![];
+
+    for (table, variant) in tables {
+        match extract_table_data(table, variant, game) {
+            Ok(i) => info.push(i),
+            Err(e) => failures.push(match e.downcast::<TableParseError>() {
+                Ok(table_error) => TableParseError {
+                    shadow_name: shadow_name.to_string(),
+                    ..table_error
+                }.into(),
+                Err(e) => e
+            })
+        }
+    }
+
+    if info.is_empty() {
+        if let Some(e) = failures.into_iter().next() {
+            return Err(e);
+        }
+        return Ok(info);
+    }
+
+    // the tabs that couldn't be read are mentioned with the ones that could, not printed
+    for e in failures {
+        info[0].warnings.push(e.to_string());
+    }
+
+    Ok(info)
+}
+
+#[allow(clippy::ptr_arg)]
 pub fn extract_table_data(table_doc: &Html, variant: &String, game: &Game) -> anyhow::Result<ShadowInfo> {
     let types = Selector::parse("tbody > tr:nth-child(1) > th").unwrap();
     let types_table: Vec<String> = table_doc.select(&types)
         .map(|t| t.inner_html().trim().to_string()).collect();
 
     let resistances = Selector::parse("tbody > tr:nth-child(2) > td").unwrap();
+    let cells: Vec<String> = table_doc.select(&resistances)
+        .map(|c| c.inner_html().trim().to_string())
+        .collect();
+
+    let table_error = |reason: &str| TableParseError {
+        shadow_name: String::new(),
+        game: game.entry_text.clone(),
+        variant: variant.to_string(),
+        headers: types_table.len(),
+        cells: cells.len(),
+        reason: reason.to_string()
+    };
+    if types_table.is_empty() && cells.is_empty() {
+        return Err(table_error("no resistance table found").into());
+    }
+    if types_table.is_empty() {
+        return Err(table_error("no element headers").into());
+    }
+    if cells.is_empty() {
+        return Err(table_error("no affinity cells").into());
+    }
 
     let mut shadow_info = ShadowInfo {
         game: game.entry_text.clone(),
-        version: game.tab_names[0].clone(),
-        variant: variant.clone(),
+        variant: variant.to_string(),
         resistances: HashMap::new(),
+        stats: None,
+        skills: vec![],
+        warnings: vec![]
     };
 
-    for (idx, element) in table_doc.select(&resistances).enumerate() {
-        let stripped = utils::strip_cell_tags(element.inner_html());
-        let res = stripped.to_string();
+    if cells.len() > types_table.len() {
+        shadow_info.warnings.push(format!(
+            "{} affinity cells for {} elements, ignored the extra ones",
+            cells.len(),
+            types_table.len()
+        ));
+    } else if cells.len() < types_table.len() {
+        shadow_info.warnings.push(format!(
+            "no affinity for {}",
+            types_table[cells.len()..].join(", ")
+        ));
+    }
 
-        if shadow_info.resistances.get(&res).is_none() {
-            shadow_info.resistances.insert(res.clone(), vec![]);
+    for (element, cell) in types_table.iter().zip(cells) {
+        // neutral is written as "-", a blank cell means the wiki is missing something
+        if cell.is_empty() {
+            shadow_info.warnings.push(format!("empty affinity for {}", element));
+            continue;
         }
 
-        shadow_info.resistances.get_mut(&res).unwrap().push(types_table[idx].to_string());
+        let res = utils::strip_cell_tags(cell);
+        shadow_info.resistances.entry(res).or_default().push(element.to_string());
     }
 
     Ok(shadow_info)
@@ -1,3 +1,6 @@
+// the baseline tests predate clippy's cast, borrow and panic message lints
+#![allow(clippy::unnecessary_cast, clippy::needless_borrow, non_fmt_panics)]
+
 use super::*;
 use scraper::node::Element;
 
@@ -46,7 +49,7 @@ fn game_section_wrapper(shadow_page_id: isize, expected_tabs: u8, game: &Game, s
     let section = game_section(&document, &game, shadow_name);
     assert!(section.is_ok());
 
-    let tabs = section.unwrap().tree.nodes().map(|n| {
+    let tabs = section.unwrap().0.tree.nodes().map(|n| {
         match n.value().as_element() {
             Some(e) => {
                 if e.attr("class").is_some() && e.attr("class").unwrap() == "tabbertab" {
@@ -125,7 +128,7 @@ fn game_section_double_heading_no_tabs() {
 // 6. appears in Journey/Answer and has sub-boss variant: https://megamitensei.fandom.com/wiki/Crying_Table
 fn game_table_wrapper(shadow_page_id: isize, game: &Game, shadow_name: String) -> anyhow::Result<Element> {
     let document = page_html(&shadow_page_id)?;
-    let section = game_section(&document, &game, shadow_name.clone())?;
+    let (section, _layout) = game_section(&document, &game, shadow_name.clone())?;
     let table_nodes = game_table(&section)?;
     let (table, _variant) = table_nodes.first().unwrap();
 
@@ -249,7 +252,7 @@ fn extract_table_data_wrapper(shadow_page_id: isize, truth: HashMap<String, Vec<
     };
 
     let mut shadow_info = vec![];
-    let section = game_section(&document, &game, shadow_name.clone()).unwrap();
+    let (section, _layout) = game_section(&document, &game, shadow_name.clone()).unwrap();
     let table_nodes = game_table(&section).unwrap();
     for (table, variant) in table_nodes {
         shadow_info.push(extract_table_data(&table, &variant, &game).unwrap());
@@ -281,3 +284,444 @@ fn extract_table_data_ok() {
 
     extract_table_data_wrapper(5302 as isize, known_data, "Liberating Idol".to_string());
 }
+
+#[test]
+fn infobox_ok() {
+    let response: Page = serde_json::from_str(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let document = Html::parse_fragment(response.content.as_str());
+
+    let infobox = infobox(&document, response.hero_image.as_ref()).unwrap();
+    assert_eq!(infobox.japanese_name, Some("泣くテーブル".to_string()));
+    assert_eq!(infobox.romaji, Some("Naku Tēburu".to_string()));
+    assert_eq!(infobox.first_appearance, Some("Persona 3".to_string()));
+    assert_eq!(infobox.arcana, vec!["Magician".to_string()]);
+    assert_eq!(infobox.species, Some("Shadow".to_string()));
+    assert!(infobox.image.unwrap().contains("17_crying_table.png"));
+}
+
+#[test]
+fn classify_per_game() {
+    let response: Page = serde_json::from_str(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let categories: Vec<String> = response.categories.iter().map(|c| c.title.clone()).collect();
+
+    let (kind, arcana) = classify(&categories, &utils::determine_game("3"));
+    assert_eq!(kind, Some(Kind::Boss));
+    assert_eq!(arcana, vec!["Magician".to_string()]);
+
+    let (kind, _) = classify(&categories, &utils::determine_game("4"));
+    assert_eq!(kind, Some(Kind::Normal));
+
+    assert_eq!(appears_in_categories(&response.categories, &utils::determine_game("4")), Some(true));
+    assert_eq!(appears_in_categories(&[], &utils::determine_game("4")), None);
+    let p3_only = vec![Category { title: "Persona 3 Shadows".to_string() }];
+    assert_eq!(appears_in_categories(&p3_only, &utils::determine_game("4")), Some(false));
+}
+
+#[test]
+fn classify_ignores_spinoffs() {
+    let categories = vec![
+        "Persona 4 Arena Shadows".to_string(),
+        "Persona 4 Arena Bosses".to_string(),
+        "Persona 3 Dancing in Moonlight Shadows".to_string()
+    ];
+    assert_eq!(classify(&categories, &utils::determine_game("4")).0, None);
+    assert_eq!(classify(&categories, &utils::determine_game("3")).0, None);
+
+    let arena_only = vec![Category { title: "Persona 4 Arena Shadows".to_string() }];
+    assert_eq!(appears_in_categories(&arena_only, &utils::determine_game("4")), Some(false));
+
+    let golden = vec!["Persona 4 Golden Bosses".to_string()];
+    assert_eq!(classify(&golden, &utils::determine_game("4")).0, Some(Kind::Boss));
+}
+
+#[test]
+fn parse_appearance_variants() {
+    let p3 = |version: Option<&str>| Some(Appearance {
+        game: "Persona 3".to_string(),
+        version: version.map(|v| v.to_string())
+    });
+
+    assert_eq!(parse_appearance("Persona 3"), p3(None));
+    assert_eq!(parse_appearance(" Persona 3 FES "), p3(Some("FES")));
+    assert_eq!(parse_appearance("Persona3 Portable"), p3(Some("Portable")));
+    assert_eq!(parse_appearance("P3FES"), p3(Some("FES")));
+    assert_eq!(parse_appearance("Persona 4 Golden").unwrap().version, Some("Golden".to_string()));
+    assert_eq!(parse_appearance("Persona Q").unwrap().game, "Persona Q".to_string());
+    assert_eq!(parse_appearance("Persona 4 Arena"), Some(Appearance { game: "Persona 4 Arena".to_string(), version: None }));
+    assert_eq!(parse_appearance("Persona 4: Dancing All Night").unwrap().game, "Persona 4: Dancing All Night".to_string());
+    assert_eq!(parse_appearance("  "), None);
+}
+
+#[test]
+fn appearances_misspelled_section() {
+    let page = Html::parse_fragment(
+        "<h2 id=\"Appearaces\">Appearaces</h2>\
+         <section id=\"Appearaces-collapsible-section\"><ul>\
+         <li><i>Persona 3</i> / <i>Persona 3 FES</i></li>\
+         <li>Persona 4 Golden</li>\
+         </ul></section>"
+    );
+
+    let found = appearances(&page);
+    assert_eq!(found.len(), 3);
+    assert!(appears_in(&page, &utils::determine_game("3a")).unwrap());
+    assert!(appears_in(&page, &utils::determine_game("4")).unwrap());
+}
+
+#[test]
+fn appears_in_answer_needs_fes() {
+    let page = Html::parse_fragment(
+        "<section id=\"Appearances-collapsible-section\"><ul>\
+         <li><i>Persona 3</i> / <i>Persona 3 Portable</i></li>\
+         </ul></section>"
+    );
+
+    assert!(appears_in(&page, &utils::determine_game("3")).unwrap());
+    assert!(!appears_in(&page, &utils::determine_game("3a")).unwrap());
+    assert!(!appears_in(&page, &utils::determine_game("4")).unwrap());
+}
+
+#[test]
+fn appearances_ok() {
+    let response: Page = serde_json::from_str(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let document = Html::parse_fragment(response.content.as_str());
+
+    let found = appearances(&document);
+    assert_eq!(found.len(), 5);
+    assert_eq!(found.iter().filter(|a| a.game == "Persona 4").count(), 2);
+}
+
+#[test]
+fn game_section_reports_layout() {
+    let document = parse_page(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let game = utils::determine_game("3");
+
+    let (section, layout) = game_section(&document, &game, "Crying Table".to_string()).unwrap();
+    assert_eq!(layout, "tabbed");
+
+    let table_nodes = game_table(&section).unwrap();
+    let variants: Vec<&str> = table_nodes.iter().map(|(_, v)| v.as_str()).collect();
+    assert_eq!(variants, vec!["Sub-Boss", "The Journey", "The Answer"]);
+}
+
+struct EverythingStats;
+
+impl Layout for EverythingStats {
+    fn name(&self) -> &'static str {
+        "everything"
+    }
+
+    fn selector(&self, _game: &Game) -> String {
+        "#Stats-collapsible-section".to_string()
+    }
+}
+
+#[test]
+fn game_section_with_custom_chain() {
+    let document = parse_page(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let game = utils::determine_game("4");
+
+    let mut chain = layouts::default_layouts();
+    chain.insert(0, Box::new(EverythingStats));
+    let (_, layout) = game_section_with(&document, &game, "Crying Table".to_string(), &chain).unwrap();
+    assert_eq!(layout, "everything");
+
+    let nothing: Vec<Box<dyn Layout>> = vec![Box::new(layouts::BareStatsTable)];
+    let missing = game_section_with(&document, &game, "Crying Table".to_string(), &nothing);
+    assert!(missing.is_err());
+}
+
+#[test]
+fn diagnose_ok() {
+    let document = parse_page(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let report = diagnostics::diagnose(&document, &utils::determine_game("3"), "Crying Table");
+
+    assert_eq!(report.layout, Some("tabbed"));
+    assert_eq!(report.attempts.len(), layouts::default_layouts().len());
+    assert_eq!(report.attempts[0].hits, Some(3));
+    assert!(report.error.is_none());
+    assert_eq!(report.tables.len(), 3);
+    assert!(report.tables.iter().all(|t| t.headers == 10 && t.cells == 10));
+    assert!(report.snippet.len() <= 603);
+}
+
+#[test]
+fn diagnose_no_layout() {
+    let document = Html::parse_fragment("<p>nothing to see here</p>");
+    let report = diagnostics::diagnose(&document, &utils::determine_game("4"), "Nobody");
+
+    assert_eq!(report.layout, None);
+    assert!(report.attempts.iter().all(|a| a.hits == Some(0)));
+    assert!(report.error.unwrap().contains("Nobody"));
+    assert!(report.snippet.contains("nothing to see here"));
+}
+
+fn resistance_table(headers: &[&str], cells: &[&str]) -> Html {
+    let headers: String = headers.iter().map(|h| format!("<th>{}</th>", h)).collect();
+    let cells: String = cells.iter().map(|c| format!("<td>{}</td>", c)).collect();
+    Html::parse_fragment(&format!("<table><tbody><tr>{}</tr><tr>{}</tr></tbody></table>", headers, cells))
+}
+
+#[test]
+fn extract_table_data_fixture_clean() {
+    let document = parse_page(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let game = utils::determine_game("3");
+    let (section, _layout) = game_section(&document, &game, "Crying Table".to_string()).unwrap();
+
+    let info = extract_tables(&game_table(&section).unwrap(), &game, "Crying Table").unwrap();
+    assert_eq!(info.len(), 3);
+    assert!(info.iter().all(|i| i.warnings.is_empty()));
+}
+
+#[test]
+fn extract_table_data_extra_cells() {
+    let game = utils::determine_game("3");
+    let table = resistance_table(&["Fire", "Ice"], &["Weak", "Null", "Repel"]);
+
+    let info = extract_table_data(&table, &"Default".to_string(), &game).unwrap();
+    assert_eq!(info.affinity("Fire"), "Weak");
+    assert_eq!(info.affinity("Ice"), "Null");
+    assert!(!info.resistances.contains_key("Repel"));
+    assert_eq!(info.warnings.len(), 1);
+}
+
+#[test]
+fn extract_table_data_missing_cells() {
+    let game = utils::determine_game("3");
+    let table = resistance_table(&["Fire", "Ice", "Elec"], &["Weak", ""]);
+
+    let info = extract_table_data(&table, &"Default".to_string(), &game).unwrap();
+    assert_eq!(info.affinity("Fire"), "Weak");
+    assert_eq!(info.warnings, vec![
+        "no affinity for Elec".to_string(),
+        "empty affinity for Ice".to_string()
+    ]);
+}
+
+#[test]
+fn extract_table_data_empty() {
+    let game = utils::determine_game("3");
+    let err = extract_table_data(&Html::parse_fragment(""), &"Default".to_string(), &game).unwrap_err();
+
+    let table_error = err.downcast::<TableParseError>().unwrap();
+    assert_eq!(table_error.headers, 0);
+    assert_eq!(table_error.cells, 0);
+}
+
+#[test]
+fn extract_tables_keeps_readable_variants() {
+    let game = utils::determine_game("3");
+    let tables = vec![
+        (resistance_table(&["Fire"], &["Weak"]), "The Journey".to_string()),
+        (Html::parse_fragment(""), "The Answer".to_string())
+    ];
+
+    let info = extract_tables(&tables, &game, "Crying Table").unwrap();
+    assert_eq!(info.len(), 1);
+    assert_eq!(info[0].variant, "The Journey");
+
+    let err = extract_tables(&tables[1..], &game, "Crying Table").unwrap_err();
+    let table_error = err.downcast::<TableParseError>().unwrap();
+    assert_eq!(table_error.shadow_name, "Crying Table");
+    assert_eq!(table_error.variant, "The Answer");
+}
+
+fn sorted_resistances(info: &ShadowInfo) -> Vec<(String, Vec<String>)> {
+    let mut resistances: Vec<(String, Vec<String>)> = info.resistances.iter()
+        .map(|(affinity, elements)| {
+            let mut elements = elements.clone();
+            elements.sort();
+            (affinity.clone(), elements)
+        })
+        .collect();
+    resistances.sort();
+    resistances
+}
+
+// crying_table.wikitext is transcribed by hand from crying_table.json, not captured, so this
+// only checks the parser against that transcription. wikitext_matches_html_live checks the wiki
+#[test]
+fn wikitext_matches_html() {
+    let document = parse_page(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let text = include_str!("../../fixtures/crying_table.wikitext");
+
+    for persona in ["3", "4"] {
+        let game = utils::determine_game(persona);
+        let (section, _layout) = game_section(&document, &game, "Crying Table".to_string()).unwrap();
+        let from_html = extract_tables(&game_table(&section).unwrap(), &game, "Crying Table").unwrap();
+        let from_wikitext = wikitext::shadow_info(text, &game, "Crying Table").unwrap();
+
+        assert_eq!(from_html.len(), from_wikitext.len());
+        for (html, wiki) in from_html.iter().zip(&from_wikitext) {
+            assert_eq!(html.variant, wiki.variant);
+            assert_eq!(sorted_resistances(html), sorted_resistances(wiki), "{}", wiki.variant);
+            assert_eq!(game_details(&section).iter().find(|d| d.0 == html.variant).unwrap().1, wiki.stats);
+            assert!(wiki.warnings.is_empty());
+        }
+    }
+}
+
+#[test]
+fn wikitext_matches_html_live() {
+    for persona in ["3", "4"] {
+        let game = utils::determine_game(persona);
+        let from_html = get_shadow_with("Crying Table", &game, Backend::Html).unwrap();
+        let from_wikitext = get_shadow_with("Crying Table", &game, Backend::Wikitext).unwrap();
+
+        assert_eq!(from_html.info.len(), from_wikitext.info.len());
+        for (html, wiki) in from_html.info.iter().zip(&from_wikitext.info) {
+            assert_eq!(html.variant, wiki.variant);
+            assert_eq!(sorted_resistances(html), sorted_resistances(wiki), "{}", wiki.variant);
+            assert!(wiki.warnings.is_empty(), "{:?}", wiki.warnings);
+        }
+    }
+}
+
+#[test]
+fn wikitext_templates() {
+    let templates = wikitext::parse_templates("{{P3 Enemy|Fire=[[Weak|wk]]|Drop={{Item|Card}}|Ice = Nu}} {{Other}}");
+
+    assert_eq!(templates.len(), 2);
+    assert_eq!(templates[0].name, "P3 Enemy");
+    assert_eq!(templates[0].params, vec![
+        ("fire".to_string(), "[[Weak|wk]]".to_string()),
+        ("drop".to_string(), "{{Item|Card}}".to_string()),
+        ("ice".to_string(), "Nu".to_string())
+    ]);
+    assert_eq!(templates[1].name, "Other");
+}
+
+#[test]
+fn wikitext_single_game_no_tabs() {
+    let text = "==Stats==\n{{P4 Enemy\n|Phys=Wk\n|Fire=Rp\n|Ice=???\n}}\n";
+    let game = utils::determine_game("4");
+
+    let info = wikitext::shadow_info(text, &game, "Nobody").unwrap();
+    assert_eq!(info.len(), 1);
+    assert_eq!(info[0].variant, "Default");
+    assert_eq!(info[0].affinity("Phys"), "Weak");
+    assert_eq!(info[0].affinity("Fire"), "Repel");
+    assert_eq!(info[0].affinity("Wind"), "Neutral");
+    assert_eq!(info[0].warnings.len(), 1);
+
+    assert!(wikitext::shadow_info("no stats here", &game, "Nobody").is_err());
+}
+
+#[test]
+fn game_details_ok() {
+    let document = parse_page(include_str!("../../fixtures/crying_table.json")).unwrap();
+    let game = utils::determine_game("3");
+    let (section, _layout) = game_section(&document, &game, "Crying Table".to_string()).unwrap();
+
+    let details = game_details(&section);
+    assert_eq!(details.len(), 3);
+
+    let (variant, stats, skills) = &details[2];
+    assert_eq!(variant, "The Answer");
+    assert_eq!(stats.clone().unwrap(), Stats {
+        level: Some(48),
+        hp: Some(1300),
+        sp: None,
+        strength: Some(35),
+        magic: Some(38),
+        endurance: Some(34),
+        agility: Some(33),
+        luck: Some(30)
+    });
+    assert_eq!(skills.len(), 8);
+    assert_eq!(skills[0], Skill {
+        name: "Agidyne".to_string(),
+        effect: "Deals heavy Fire damage to one foe.".to_string()
+    });
+}
+
+#[test]
+fn parse_index_fixture() {
+    let page = Html::parse_fragment(include_str!("../../fixtures/shadow_index.html"));
+    let entries = parse_index(&page);
+
+    assert_eq!(entries, vec![
+        IndexEntry {
+            name: "Cowardly Maya".to_string(),
+            arcana: Some("Magician".to_string()),
+            level: Some("2".to_string()),
+            area: Some("Thebel".to_string())
+        },
+        IndexEntry {
+            name: "Crying Table".to_string(),
+            arcana: Some("Magician".to_string()),
+            level: Some("20".to_string()),
+            area: Some("Arqa".to_string())
+        },
+        IndexEntry {
+            name: "Dancing Hand".to_string(),
+            arcana: Some("Priestess".to_string()),
+            level: Some("7".to_string()),
+            area: Some("Thebel".to_string())
+        },
+        IndexEntry {
+            name: "Arcana Priestess".to_string(),
+            arcana: Some("Priestess".to_string()),
+            level: None,
+            area: None
+        },
+        IndexEntry {
+            name: "Hanged Statue".to_string(),
+            arcana: Some("Hanged Man".to_string()),
+            level: None,
+            area: None
+        }
+    ]);
+}
+
+#[test]
+fn skip_reasons() {
+    let game = utils::determine_game("3");
+    let not_in_game: anyhow::Error = errors::NoShadowError {
+        name: "Nyx".to_string(),
+        game: game.entry_text.clone()
+    }.into();
+    let no_section: anyhow::Error = NoVariantError {
+        shadow_name: "Nyx".to_string(),
+        game: game.entry_text.clone(),
+        variant: game.tab_names.clone()
+    }.into();
+    let no_table: anyhow::Error = TableParseError {
+        shadow_name: "Nyx".to_string(),
+        game: game.entry_text.clone(),
+        variant: "Default".to_string(),
+        headers: 0,
+        cells: 0,
+        reason: "no headers".to_string()
+    }.into();
+    let parse: anyhow::Error = serde_json::from_str::<Page>("{").unwrap_err().into();
+
+    assert_eq!(SkipReason::of(&not_in_game), SkipReason::NotInGame);
+    assert_eq!(SkipReason::of(&no_section), SkipReason::NoSection);
+    assert_eq!(SkipReason::of(&no_table), SkipReason::NoTable);
+    assert_eq!(SkipReason::of(&parse), SkipReason::Parse);
+}
+
+#[test]
+fn appears_in_categories_answer_needs_fes() {
+    let answer = utils::determine_game("3a");
+    let category = |title: &str| Category { title: title.to_string() };
+
+    assert_eq!(appears_in_categories(&[category("Persona 3 Shadows")], &answer), Some(true));
+    assert_eq!(appears_in_categories(&[category("Persona 3 Portable Shadows")], &answer), Some(false));
+    assert_eq!(
+        appears_in_categories(&[category("Persona 3 Portable Shadows"), category("Persona 3 FES Shadows")], &answer),
+        Some(true)
+    );
+}
+
+#[test]
+fn spinoff_appearance_not_in_game() {
+    let page = Html::parse_fragment(
+        "<section id=\"Appearances-collapsible-section\"><ul>\
+         <li>Persona 4 Arena</li>\
+         </ul></section>"
+    );
+
+    assert!(!appears_in(&page, &utils::determine_game("4")).unwrap());
+}
@@ -0,0 +1,352 @@
+// Reads resistances from a page's raw wikitext instead of the rendered html. The stat templates
+// are filled in by editors with the same parameter names everywhere, while the html around them
+// depends on whoever wrote the page
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::errors::NoVariantError;
+use crate::{config, utils};
+use super::{fetch_json, Game, ShadowInfo, Stats};
+
+#[derive(Deserialize, Debug)]
+struct RevisionsResponse {
+    query: RevisionsQuery
+}
+
+#[derive(Deserialize, Debug)]
+struct RevisionsQuery {
+    pages: HashMap<String, RevisionsPage>
+}
+
+#[derive(Deserialize, Debug)]
+struct RevisionsPage {
+    #[serde(default)]
+    revisions: Vec<Revision>
+}
+
+#[derive(Deserialize, Debug)]
+struct Revision {
+    #[serde(default)]
+    slots: Option<Slots>,
+    // older MediaWiki versions put the content on the revision itself
+    #[serde(rename = "*", default)]
+    content: Option<String>
+}
+
+#[derive(Deserialize, Debug)]
+struct Slots {
+    main: Slot
+}
+
+#[derive(Deserialize, Debug)]
+struct Slot {
+    #[serde(rename = "*")]
+    content: String
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Template {
+    pub name: String,
+    // named parameters only, keys lowercased
+    pub params: Vec<(String, String)>
+}
+
+pub fn page_wikitext(page_id: &isize) -> anyhow::Result<String> {
+    let endpoint = format!(
+        "{}/api.php?format=json&action=query&prop=revisions&rvprop=content&rvslots=main&pageids={}",
+        config::wiki_url(),
+        page_id
+    );
+
+    let body: RevisionsResponse = fetch_json(&endpoint)?;
+    let revision = body.query.pages.into_values()
+        .flat_map(|p| p.revisions)
+        .next();
+
+    match revision {
+        Some(Revision { slots: Some(slots), .. }) => Ok(slots.main.content),
+        Some(Revision { content: Some(content), .. }) => Ok(content),
+        _ => anyhow::bail!("No revisions found for page id: {}", page_id)
+    }
+}
+
+// The same as the html path's game_section -> game_table -> extract_table_data, one ShadowInfo per tab
+pub fn shadow_info(text: &str, game: &Game, shadow_name: &str) -> anyhow::Result<Vec<ShadowInfo>> {
+    let no_variant = || NoVariantError {
+        shadow_name: shadow_name.to_string(),
+        game: game.entry_text.clone(),
+        variant: game.tab_names.clone()
+    };
+
+    let section = game_section(stats_section(text), game).ok_or_else(no_variant)?;
+
+    let info: Vec<ShadowInfo> = variants(section).iter()
+        .filter_map(|(variant, content)| {
+            parse_templates(content).iter()
+                .find_map(|t| template_info(t, variant, game))
+        })
+        .collect();
+
+    if info.is_empty() {
+        return Err(no_variant().into());
+    }
+
+    Ok(info)
+}
+
+// "=== Persona 3 ===" -> (3, "Persona 3")
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let line = line.trim();
+    let level = line.chars().take_while(|c| *c == '=').count();
+    if level < 2 || !line.ends_with(&"=".repeat(level)) || line.len() <= level * 2 {
+        return None;
+    }
+
+    Some((level, line[level..line.len() - level].trim()))
+}
+
+// the lines under the first heading matching, up to the next heading of the same or a higher level
+fn section(text: &str, matches: impl Fn(&str) -> bool) -> Option<&str> {
+    let mut start = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        if let Some((level, title)) = heading(line) {
+            match start {
+                Some((start_level, start_offset)) if level <= start_level => {
+                    return Some(&text[start_offset..offset]);
+                },
+                None if matches(title) => start = Some((level, offset + line.len())),
+                _ => {}
+            }
+        }
+        offset += line.len();
+    }
+
+    start.map(|(_, start_offset)| &text[start_offset..])
+}
+
+fn stats_section(text: &str) -> &str {
+    section(text, |title| title.eq_ignore_ascii_case("stats")).unwrap_or(text)
+}
+
+// pages for shadows in a single game don't have game headings in their stats
+fn game_section<'a>(stats: &'a str, game: &Game) -> Option<&'a str> {
+    let has_game_headings = stats.lines()
+        .filter_map(heading)
+        .any(|(_, title)| title.starts_with("Persona"));
+
+    if has_game_headings {
+        section(stats, |title| title.starts_with(&game.entry_text))
+    } else {
+        Some(stats)
+    }
+}
+
+// where the content of the first tabber starts and ends
+fn tabber_bounds(text: &str) -> Option<(usize, usize)> {
+    const OPEN: &str = "<tabber>";
+    const CLOSE: &str = "</tabber>";
+
+    let content_start = text.find(OPEN)? + OPEN.len();
+    let mut depth = 1;
+    let mut idx = content_start;
+
+    while idx < text.len() {
+        let rest = &text[idx..];
+        if rest.starts_with(OPEN) {
+            depth += 1;
+            idx += OPEN.len();
+        } else if rest.starts_with(CLOSE) {
+            depth -= 1;
+            if depth == 0 {
+                return Some((content_start, idx));
+            }
+            idx += CLOSE.len();
+        } else {
+            idx += rest.chars().next().map_or(1, |c| c.len_utf8());
+        }
+    }
+
+    None
+}
+
+// splits a tabber's content on |-| separators that aren't inside a nested tabber
+fn split_tabs(content: &str) -> Vec<&str> {
+    let mut tabs = vec![];
+    let mut depth = 0;
+    let mut tab_start = 0;
+    let mut idx = 0;
+
+    while idx < content.len() {
+        let rest = &content[idx..];
+        if rest.starts_with("<tabber>") {
+            depth += 1;
+        } else if rest.starts_with("</tabber>") {
+            depth -= 1;
+        } else if depth == 0 && rest.starts_with("|-|") {
+            tabs.push(&content[tab_start..idx]);
+            tab_start = idx + 3;
+            idx += 3;
+            continue;
+        }
+        idx += rest.chars().next().map_or(1, |c| c.len_utf8());
+    }
+    tabs.push(&content[tab_start..]);
+
+    tabs
+}
+
+// (variant, wikitext) for every tab in the section. Like the html path, tabs nested inside
+// version tabs (Portable) win over the version tabs themselves
+fn variants(section: &str) -> Vec<(String, &str)> {
+    let (content_start, content_end) = match tabber_bounds(section) {
+        Some(b) => b,
+        None => return vec![("Default".to_string(), section)]
+    };
+
+    let tabs: Vec<(String, &str)> = split_tabs(&section[content_start..content_end]).into_iter()
+        .filter_map(|tab| {
+            let (name, content) = tab.split_once('=')?;
+            Some((name.trim().to_string(), content))
+        })
+        .collect();
+
+    if tabs.iter().any(|(_, content)| tabber_bounds(content).is_some()) {
+        tabs.iter()
+            .flat_map(|(_, content)| variants(content))
+            .collect()
+    } else {
+        tabs
+    }
+}
+
+// every top level {{template}} in the text, nested ones stay part of their parameter values
+pub fn parse_templates(text: &str) -> Vec<Template> {
+    let mut templates = vec![];
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let body = &rest[start + 2..];
+        let end = match closing_braces(body) {
+            Some(e) => e,
+            None => break
+        };
+
+        let mut parts = split_params(&body[..end]).into_iter();
+        let name = parts.next().unwrap_or("").trim().to_string();
+        let params = parts
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+            .collect();
+
+        templates.push(Template { name, params });
+        rest = &body[end + 2..];
+    }
+
+    templates
+}
+
+// index of the }} closing a template whose {{ was just consumed
+fn closing_braces(body: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut idx = 0;
+
+    while idx < body.len() {
+        let rest = &body[idx..];
+        if rest.starts_with("{{") {
+            depth += 1;
+            idx += 2;
+        } else if rest.starts_with("}}") {
+            if depth == 0 {
+                return Some(idx);
+            }
+            depth -= 1;
+            idx += 2;
+        } else {
+            idx += rest.chars().next().map_or(1, |c| c.len_utf8());
+        }
+    }
+
+    None
+}
+
+// pipes inside links or nested templates don't separate parameters
+fn split_params(body: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut part_start = 0;
+
+    for (idx, c) in body.char_indices() {
+        let rest = &body[idx..];
+        if rest.starts_with("{{") || rest.starts_with("[[") {
+            depth += 1;
+        } else if (rest.starts_with("}}") || rest.starts_with("]]")) && depth > 0 {
+            depth -= 1;
+        } else if c == '|' && depth == 0 {
+            parts.push(&body[part_start..idx]);
+            part_start = idx + 1;
+        }
+    }
+    parts.push(&body[part_start..]);
+
+    parts
+}
+
+// "[[Weak]]", "{{Wk}}" or "Resist" -> the affinity names used in resistance tables
+fn affinity_value(value: &str) -> Option<String> {
+    let cleaned = value.replace(['[', ']', '{', '}', '\''], "");
+    let cleaned = cleaned.rsplit('|').next().unwrap_or("").trim().to_lowercase();
+
+    let abbreviated = match cleaned.as_str() {
+        "wk" => "weak",
+        "str" | "rs" | "res" => "strong",
+        "nu" | "nul" => "null",
+        "rp" | "rpl" => "repel",
+        "dr" | "ab" => "drain",
+        "" => "neutral",
+        other => other
+    };
+
+    utils::normalize_affinity(abbreviated)
+}
+
+// a template is the stat template when its parameters name the game's elements
+fn template_info(template: &Template, variant: &str, game: &Game) -> Option<ShadowInfo> {
+    let elements = utils::game_elements(game);
+    let mut by_element: HashMap<&str, &str> = HashMap::new();
+    for (key, value) in &template.params {
+        let element = utils::normalize_element(key);
+        if let Some(e) = elements.iter().find(|e| **e == element) {
+            by_element.insert(e, value);
+        }
+    }
+
+    if by_element.len() < 2 {
+        return None;
+    }
+
+    let mut info = ShadowInfo {
+        game: game.entry_text.clone(),
+        variant: variant.to_string(),
+        resistances: HashMap::new(),
+        stats: None,
+        skills: vec![],
+        warnings: vec![]
+    };
+
+    let mut stats = Stats::default();
+    let has_stats = template.params.iter().fold(false, |found, (key, value)| stats.set(key, value) || found);
+    if has_stats {
+        info.stats = Some(stats);
+    }
+
+    for element in elements {
+        let value = by_element.get(element).copied().unwrap_or("");
+        match affinity_value(value) {
+            Some(affinity) => info.resistances.entry(affinity).or_default().push(element.to_string()),
+            None => info.warnings.push(format!("unknown affinity for {}: {}", element, value))
+        }
+    }
+
+    Some(info)
+}